@@ -0,0 +1,167 @@
+//! Logical ordering of right-to-left text.
+//!
+//! PDF content draws glyphs wherever the producer placed them, so Arabic and Hebrew runs come
+//! out in visual (left to right on the page) or arbitrary order. Glyphs of a block are first
+//! put in visual order by their position along the baseline, keeping combining marks with the
+//! glyph they were drawn after. The Unicode bidi algorithm run over that visual string then
+//! reverses the right-to-left runs glyph by glyph (and, for a right-to-left paragraph, the order
+//! of the runs), which yields the logical order for all but deeply nested embeddings. The text
+//! of one glyph, such as a lam-alef ligature mapped to two letters, is already in logical order
+//! and is never reversed.
+
+use std::ops::Range;
+
+use unicode_bidi::{BidiClass, BidiInfo, Level, bidi_class};
+
+use crate::{TextGlyph, project_glyph};
+
+/// Glyphs narrower than this fraction of the font size are attached to the previous glyph.
+const MARK_WIDTH_RATIO: f32 = 0.05;
+
+/// Whether the text contains a strong right-to-left character.
+pub fn has_rtl(text: &str) -> bool {
+    text.chars()
+        .any(|c| matches!(bidi_class(c), BidiClass::R | BidiClass::AL))
+}
+
+/// Whether right-to-left characters outnumber left-to-right ones.
+pub fn is_rtl_dominant(text: &str) -> bool {
+    let (mut rtl, mut ltr) = (0usize, 0usize);
+    for c in text.chars() {
+        match bidi_class(c) {
+            BidiClass::R | BidiClass::AL => rtl += 1,
+            BidiClass::L => ltr += 1,
+            _ => {}
+        }
+    }
+    rtl > ltr
+}
+
+/// Text of a block's glyphs in logical order, or `None` when it has no right-to-left text.
+pub fn logical_text(glyphs: &[TextGlyph]) -> Option<String> {
+    let first = glyphs.first()?;
+    if !glyphs.iter().any(|glyph| has_rtl(&glyph.text)) {
+        return None;
+    }
+    let direction = first.direction;
+    let mut clusters: Vec<(f32, String)> = Vec::new();
+    for glyph in glyphs {
+        let (start, end) = project_glyph(glyph, direction);
+        let is_mark = glyph.text.chars().all(is_combining_mark)
+            || end - start < glyph.font_size.abs() * MARK_WIDTH_RATIO;
+        match clusters.last_mut() {
+            Some((_, text)) if is_mark => text.push_str(&glyph.text),
+            _ => clusters.push(((start + end) / 2.0, glyph.text.clone())),
+        }
+    }
+    clusters.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut visual = String::new();
+    let mut unit_starts = Vec::with_capacity(clusters.len());
+    for (_, text) in clusters {
+        unit_starts.push(visual.len());
+        visual.push_str(&text);
+    }
+    Some(reorder(&visual, &unit_starts))
+}
+
+/// Apply the bidi reordering to `text`, made of units (glyphs with their marks) starting at
+/// the byte offsets `unit_starts`. Right-to-left runs are reversed unit by unit.
+fn reorder(text: &str, unit_starts: &[usize]) -> String {
+    let base_level = if is_rtl_dominant(text) {
+        Level::rtl()
+    } else {
+        Level::ltr()
+    };
+    let info = BidiInfo::new(text, Some(base_level));
+    let mut ordered = String::with_capacity(text.len());
+    for paragraph in &info.paragraphs {
+        let (levels, runs) = info.visual_runs(paragraph, paragraph.range.clone());
+        for run in runs {
+            if levels[run.start].is_rtl() {
+                for unit in units(text, run.clone(), unit_starts).into_iter().rev() {
+                    ordered.push_str(unit);
+                }
+            } else {
+                ordered.push_str(&text[run]);
+            }
+        }
+    }
+    ordered
+}
+
+/// Split the `range` of `text` at the unit boundaries inside it.
+fn units<'a>(text: &'a str, range: Range<usize>, unit_starts: &[usize]) -> Vec<&'a str> {
+    let mut units = Vec::new();
+    let mut start = range.start;
+    for &boundary in unit_starts {
+        if boundary > start && boundary < range.end {
+            units.push(&text[start..boundary]);
+            start = boundary;
+        }
+    }
+    if start < range.end {
+        units.push(&text[start..range.end]);
+    }
+    units
+}
+
+/// Combining marks of the Hebrew and Arabic blocks and the generic combining diacritics.
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{0591}'..='\u{05BD}'
+            | '\u{05BF}'
+            | '\u{05C1}'..='\u{05C2}'
+            | '\u{05C4}'..='\u{05C5}'
+            | '\u{05C7}'
+            | '\u{0610}'..='\u{061A}'
+            | '\u{064B}'..='\u{065F}'
+            | '\u{0670}'
+            | '\u{06D6}'..='\u{06DC}'
+            | '\u{06DF}'..='\u{06E4}'
+            | '\u{06E7}'..='\u{06E8}'
+            | '\u{06EA}'..='\u{06ED}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Visual text of `units` with the byte offset at which each one starts.
+    fn visual(units: &[&str]) -> (String, Vec<usize>) {
+        let mut text = String::new();
+        let mut starts = Vec::new();
+        for unit in units {
+            starts.push(text.len());
+            text.push_str(unit);
+        }
+        (text, starts)
+    }
+
+    #[test]
+    fn reverses_right_to_left_glyphs() {
+        let (text, starts) = visual(&["ם", "ו", "ל", "ש"]);
+        assert_eq!(reorder(&text, &starts), "שלום");
+    }
+
+    #[test]
+    fn keeps_ligature_text_in_logical_order() {
+        // The lam-alef ligature U+FEFB maps to lam followed by alef.
+        let (text, starts) = visual(&["لا", "ب"]);
+        assert_eq!(reorder(&text, &starts), "بلا");
+    }
+
+    #[test]
+    fn keeps_marks_with_their_glyph() {
+        let (text, starts) = visual(&["ב", "ש\u{05B8}"]);
+        assert_eq!(reorder(&text, &starts), "ש\u{05B8}ב");
+    }
+
+    #[test]
+    fn left_to_right_runs_stay_in_place() {
+        let (text, starts) = visual(&["P", "D", "F", " ", "ן", "ב"]);
+        assert_eq!(reorder(&text, &starts), "PDF בן");
+    }
+}
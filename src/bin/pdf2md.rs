@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use pdfvectorizer::markdown::{ImageExport, MarkdownError, export_markdown};
+use thiserror::Error;
+
+#[derive(Debug, Parser)]
+#[command(about = "Convert a PDF into Markdown using the layout analysis of pdfvectorizer.")]
+struct Args {
+    /// Input PDF file
+    input: PathBuf,
+
+    /// Output Markdown path. Defaults to replacing the input extension with .md
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Zero-based page indices to convert, separated by commas. Defaults to all pages
+    #[arg(long, value_delimiter = ',')]
+    pages: Option<Vec<usize>>,
+
+    /// Save embedded images into `<output stem>_images` next to the Markdown file
+    #[arg(long)]
+    save_images: bool,
+}
+
+#[derive(Debug, Error)]
+enum Pdf2MdError {
+    #[error("input path is not valid UTF-8: {0}")]
+    InputPath(PathBuf),
+    #[error("failed to convert {0}: {1}")]
+    Export(PathBuf, MarkdownError),
+    #[error("failed to write Markdown to {0}: {1}")]
+    WriteOutput(PathBuf, std::io::Error),
+}
+
+fn main() -> Result<(), Pdf2MdError> {
+    let args = Args::parse();
+
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output(&args.input));
+    let input = args
+        .input
+        .to_str()
+        .ok_or_else(|| Pdf2MdError::InputPath(args.input.clone()))?;
+
+    let image_dir_name = format!(
+        "{}_images",
+        output
+            .file_stem()
+            .map(|stem| stem.to_string_lossy())
+            .unwrap_or_default()
+    );
+    let image_dir = output
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(&image_dir_name);
+    let export = args.save_images.then(|| ImageExport {
+        directory: &image_dir,
+        link_prefix: &image_dir_name,
+    });
+
+    let markdown = export_markdown(input, args.pages.as_deref(), export.as_ref())
+        .map_err(|e| Pdf2MdError::Export(args.input.clone(), e))?;
+    fs::write(&output, markdown).map_err(|e| Pdf2MdError::WriteOutput(output.clone(), e))?;
+    println!("Saved Markdown to {}", output.display());
+    Ok(())
+}
+
+fn default_output(input: &Path) -> PathBuf {
+    let mut candidate = input.to_path_buf();
+    candidate.set_extension("md");
+    candidate
+}
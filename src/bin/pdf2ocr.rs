@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use pdfvectorizer::ocr_xml::{OcrXmlError, OcrXmlFormat, export_ocr_xml};
+use thiserror::Error;
+
+#[derive(Debug, Parser)]
+#[command(about = "Export the text layout of a PDF as hOCR or ALTO XML.")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write hOCR (XHTML). Defaults to replacing the input extension with .hocr
+    Hocr(ExportArgs),
+    /// Write ALTO v4 XML. Defaults to replacing the input extension with .xml
+    Alto(ExportArgs),
+}
+
+#[derive(Debug, ClapArgs)]
+struct ExportArgs {
+    /// Input PDF file
+    input: PathBuf,
+
+    /// Output path
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Zero-based page indices to export, separated by commas. Defaults to all pages
+    #[arg(long, value_delimiter = ',')]
+    pages: Option<Vec<usize>>,
+
+    /// Resolution of the pixel coordinates
+    #[arg(long, default_value_t = 300.0)]
+    dpi: f32,
+}
+
+#[derive(Debug, Error)]
+enum Pdf2OcrError {
+    #[error("input path is not valid UTF-8: {0}")]
+    InputPath(PathBuf),
+    #[error("failed to export {0}: {1}")]
+    Export(PathBuf, OcrXmlError),
+    #[error("failed to write output to {0}: {1}")]
+    WriteOutput(PathBuf, std::io::Error),
+}
+
+fn main() -> Result<(), Pdf2OcrError> {
+    let (args, format, extension) = match Args::parse().command {
+        Command::Hocr(args) => (args, OcrXmlFormat::Hocr, "hocr"),
+        Command::Alto(args) => (args, OcrXmlFormat::Alto, "xml"),
+    };
+
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output(&args.input, extension));
+    let input = args
+        .input
+        .to_str()
+        .ok_or_else(|| Pdf2OcrError::InputPath(args.input.clone()))?;
+
+    let xml = export_ocr_xml(input, args.pages.as_deref(), args.dpi, format)
+        .map_err(|e| Pdf2OcrError::Export(args.input.clone(), e))?;
+    fs::write(&output, xml).map_err(|e| Pdf2OcrError::WriteOutput(output.clone(), e))?;
+    println!("Saved {:?} output to {}", format, output.display());
+    Ok(())
+}
+
+fn default_output(input: &Path, extension: &str) -> PathBuf {
+    let mut candidate = input.to_path_buf();
+    candidate.set_extension(extension);
+    candidate
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use pdfvectorizer::svg::{SvgError, page_to_svg};
+use thiserror::Error;
+
+#[derive(Debug, Parser)]
+#[command(about = "Convert a PDF page into SVG without rasterizing it.")]
+struct Args {
+    /// Input PDF file
+    input: PathBuf,
+
+    /// Output SVG path. Defaults to replacing the input extension with .svg
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Zero-based index of the page to convert
+    #[arg(long, default_value_t = 0)]
+    page: usize,
+}
+
+#[derive(Debug, Error)]
+enum Pdf2SvgError {
+    #[error("input path is not valid UTF-8: {0}")]
+    InputPath(PathBuf),
+    #[error("failed to convert page {1} of {0}: {2}")]
+    Export(PathBuf, usize, SvgError),
+    #[error("failed to write SVG to {0}: {1}")]
+    WriteOutput(PathBuf, std::io::Error),
+}
+
+fn main() -> Result<(), Pdf2SvgError> {
+    let args = Args::parse();
+
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output(&args.input));
+    let input = args
+        .input
+        .to_str()
+        .ok_or_else(|| Pdf2SvgError::InputPath(args.input.clone()))?;
+
+    let svg = page_to_svg(input, args.page)
+        .map_err(|e| Pdf2SvgError::Export(args.input.clone(), args.page, e))?;
+    fs::write(&output, svg).map_err(|e| Pdf2SvgError::WriteOutput(output.clone(), e))?;
+    println!("Saved SVG to {}", output.display());
+    Ok(())
+}
+
+fn default_output(input: &Path) -> PathBuf {
+    let mut candidate = input.to_path_buf();
+    candidate.set_extension("svg");
+    candidate
+}
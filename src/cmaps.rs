@@ -0,0 +1,248 @@
+//! Predefined CMaps for Japanese CID-keyed fonts.
+//!
+//! Type0 fonts may name a predefined CMap in `/Encoding` instead of embedding one. Without a
+//! `/ToUnicode` CMap the text has to be recovered from that encoding: Shift-JIS codes
+//! (`90ms-RKSJ`) and UCS-2 codes (`UniJIS-UCS2`) identify their character directly, while
+//! `Identity` codes are CIDs that are mapped to Unicode through the Adobe-Japan1 collection.
+//!
+//! CIDs follow the layout of the Adobe-Japan1 `H` CMap: proportional Latin from CID 1,
+//! half-width Latin from 231, half-width katakana from 326 and JIS X 0208 from 633. Names
+//! ending in `-V` select vertical writing; their vertical-form CIDs are not distinguished from
+//! the horizontal ones, which only matters for glyph widths.
+
+mod jis0208;
+
+use std::sync::OnceLock;
+
+use jis0208::JIS0208;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeScheme {
+    /// Two-byte codes that are CIDs.
+    Identity,
+    /// Microsoft Shift-JIS; single-byte Latin maps to proportional CIDs when `proportional`.
+    ShiftJis { proportional: bool },
+    /// Two-byte UCS-2 codes; Latin maps to half-width CIDs when `half_width`.
+    Ucs2 { half_width: bool },
+}
+
+/// A predefined CMap named by the `/Encoding` of a Type0 font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PredefinedCMap {
+    scheme: CodeScheme,
+    vertical: bool,
+}
+
+impl PredefinedCMap {
+    pub const IDENTITY_H: Self = Self {
+        scheme: CodeScheme::Identity,
+        vertical: false,
+    };
+
+    /// Resolve a CMap name such as `Identity-V`, `90ms-RKSJ-H` or `UniJIS-UCS2-H`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (base, vertical) = match name.rsplit_once('-')? {
+            (base, "H") => (base, false),
+            (base, "V") => (base, true),
+            _ => return None,
+        };
+        let scheme = match base {
+            "Identity" => CodeScheme::Identity,
+            "90ms-RKSJ" => CodeScheme::ShiftJis {
+                proportional: false,
+            },
+            "90msp-RKSJ" => CodeScheme::ShiftJis { proportional: true },
+            "UniJIS-UCS2" => CodeScheme::Ucs2 { half_width: false },
+            "UniJIS-UCS2-HW" => CodeScheme::Ucs2 { half_width: true },
+            _ => return None,
+        };
+        Some(Self { scheme, vertical })
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.vertical
+    }
+
+    /// Whether codes without a `/ToUnicode` entry fall back to the CID collection.
+    pub fn is_identity(&self) -> bool {
+        self.scheme == CodeScheme::Identity
+    }
+
+    /// Read the next character code from `bytes`, returning it with its length in bytes.
+    ///
+    /// Returns `None` when the remaining bytes are a truncated multi-byte code.
+    pub fn next_code(&self, bytes: &[u8]) -> Option<(u16, usize)> {
+        let first = *bytes.first()?;
+        let two_bytes = match self.scheme {
+            CodeScheme::Identity | CodeScheme::Ucs2 { .. } => true,
+            CodeScheme::ShiftJis { .. } => is_shift_jis_lead(first),
+        };
+        if !two_bytes {
+            return Some((first as u16, 1));
+        }
+        let second = *bytes.get(1)?;
+        Some((u16::from_be_bytes([first, second]), 2))
+    }
+
+    /// CID selected by a character code, used to look up glyph widths.
+    pub fn cid(&self, code: u16) -> Option<u16> {
+        match self.scheme {
+            CodeScheme::Identity => Some(code),
+            CodeScheme::ShiftJis { proportional } => match code {
+                0x20..=0x7E if proportional => Some(code - 0x20 + 1),
+                0x20..=0x7E => Some(code - 0x20 + 231),
+                0xA1..=0xDF => Some(code - 0xA1 + 326),
+                0x100.. => shift_jis_to_jis(code).and_then(|(row, cell)| jis_cid(row, cell)),
+                _ => None,
+            },
+            CodeScheme::Ucs2 { half_width } => unicode_cid(code, half_width),
+        }
+    }
+
+    /// Character identified by the code itself, for encodings that are not CID-based.
+    pub fn unicode(&self, code: u16) -> Option<char> {
+        match self.scheme {
+            CodeScheme::Identity => None,
+            CodeScheme::ShiftJis { .. } => match code {
+                0x20..=0x7E => char::from_u32(code as u32),
+                0xA1..=0xDF => char::from_u32(0xFF61 + (code - 0xA1) as u32),
+                0x100.. => shift_jis_to_jis(code).and_then(|(row, cell)| jis_unicode(row, cell)),
+                _ => None,
+            },
+            CodeScheme::Ucs2 { .. } => char::from_u32(code as u32),
+        }
+    }
+}
+
+/// Unicode character of an Adobe-Japan1 CID.
+pub fn japan1_unicode(cid: u16) -> Option<char> {
+    match cid {
+        61 => Some('\u{A5}'),
+        95 => Some('\u{203E}'),
+        1..=95 => char::from_u32((cid - 1 + 0x20) as u32),
+        231..=325 => char::from_u32((cid - 231 + 0x20) as u32),
+        326..=388 => char::from_u32((cid - 326) as u32 + 0xFF61),
+        _ => cid_jis(cid).and_then(|(row, cell)| jis_unicode(row, cell)),
+    }
+}
+
+fn is_shift_jis_lead(byte: u8) -> bool {
+    matches!(byte, 0x81..=0x9F | 0xE0..=0xFC)
+}
+
+/// Convert a two-byte Shift-JIS code to a JIS X 0208 row and cell, both starting at 1.
+fn shift_jis_to_jis(code: u16) -> Option<(u8, u8)> {
+    let [lead, trail] = code.to_be_bytes();
+    let row = match lead {
+        0x81..=0x9F => (lead - 0x81) * 2 + 1,
+        0xE0..=0xEF => (lead - 0xC1) * 2 + 1,
+        _ => return None,
+    };
+    match trail {
+        0x40..=0x7E => Some((row, trail - 0x3F)),
+        0x80..=0x9E => Some((row, trail - 0x40)),
+        0x9F..=0xFC => Some((row + 1, trail - 0x9E)),
+        _ => None,
+    }
+}
+
+fn jis_unicode(row: u8, cell: u8) -> Option<char> {
+    let index = (row as usize - 1) * 94 + (cell as usize - 1);
+    match JIS0208.get(index) {
+        Some(&value) if value != 0 => char::from_u32(value as u32),
+        _ => None,
+    }
+}
+
+/// Runs of non-kanji JIS X 0208 codes and their first Adobe-Japan1 CID.
+const JIS_CID_RANGES: [(u16, u16, u16); 18] = [
+    (0x2121, 0x217E, 633),
+    (0x2221, 0x222E, 727),
+    (0x223A, 0x2241, 741),
+    (0x224A, 0x2250, 749),
+    (0x225C, 0x226A, 756),
+    (0x2272, 0x2279, 771),
+    (0x227E, 0x227E, 779),
+    (0x2330, 0x2339, 780),
+    (0x2341, 0x235A, 790),
+    (0x2361, 0x237A, 816),
+    (0x2421, 0x2473, 842),
+    (0x2521, 0x2576, 925),
+    (0x2621, 0x2638, 1011),
+    (0x2641, 0x2658, 1035),
+    (0x2721, 0x2741, 1059),
+    (0x2751, 0x2771, 1092),
+    (0x2821, 0x2840, 7479),
+    (0x7425, 0x7426, 8284),
+];
+
+/// First CIDs and sizes of the JIS level 1 (rows 16-47) and level 2 (rows 48-84) kanji.
+const LEVEL1_KANJI: (u16, u16) = (1125, 2965);
+const LEVEL2_KANJI: (u16, u16) = (4090, 3388);
+
+fn jis_cid(row: u8, cell: u8) -> Option<u16> {
+    let code = ((row as u16 + 0x20) << 8) | (cell as u16 + 0x20);
+    if let Some(&(first, _, cid)) = JIS_CID_RANGES
+        .iter()
+        .find(|&&(first, last, _)| (first..=last).contains(&code))
+    {
+        return Some(cid + (code - first));
+    }
+    let (first_row, (first_cid, count)) = match row {
+        16..=47 => (16, LEVEL1_KANJI),
+        48..=84 => (48, LEVEL2_KANJI),
+        _ => return None,
+    };
+    let index = (row - first_row) as u16 * 94 + (cell - 1) as u16;
+    (index < count).then_some(first_cid + index)
+}
+
+fn cid_jis(cid: u16) -> Option<(u8, u8)> {
+    let split = |code: u16| ((code >> 8) as u8 - 0x20, (code & 0xFF) as u8 - 0x20);
+    if let Some(&(first, _, first_cid)) = JIS_CID_RANGES
+        .iter()
+        .find(|&&(first, last, first_cid)| (first_cid..=first_cid + (last - first)).contains(&cid))
+    {
+        return Some(split(first + (cid - first_cid)));
+    }
+    let (first_row, first_cid) = [(16, LEVEL1_KANJI), (48, LEVEL2_KANJI)]
+        .into_iter()
+        .find(|&(_, (first_cid, count))| (first_cid..first_cid + count).contains(&cid))
+        .map(|(first_row, (first_cid, _))| (first_row, first_cid))?;
+    let index = cid - first_cid;
+    Some((first_row + (index / 94) as u8, (index % 94) as u8 + 1))
+}
+
+/// Adobe-Japan1 CID of a UCS-2 code, following `UniJIS-UCS2-H`.
+fn unicode_cid(code: u16, half_width: bool) -> Option<u16> {
+    match code {
+        0x20..=0x7E if half_width => Some(code - 0x20 + 231),
+        0x20..=0x7E => Some(code - 0x20 + 1),
+        0xA5 => Some(61),
+        0x203E => Some(95),
+        0xFF61..=0xFF9F => Some(code - 0xFF61 + 326),
+        _ => {
+            let index = unicode_to_jis()
+                .binary_search_by_key(&code, |&(unicode, _)| unicode)
+                .ok()?;
+            let jis = unicode_to_jis()[index].1 as usize;
+            jis_cid((jis / 94) as u8 + 1, (jis % 94) as u8 + 1)
+        }
+    }
+}
+
+/// Inverse of [`JIS0208`] sorted by Unicode, keeping the first cell of duplicated characters.
+fn unicode_to_jis() -> &'static [(u16, u16)] {
+    static TABLE: OnceLock<Vec<(u16, u16)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table: Vec<(u16, u16)> = JIS0208
+            .iter()
+            .enumerate()
+            .filter(|&(_, &unicode)| unicode != 0)
+            .map(|(index, &unicode)| (unicode, index as u16))
+            .collect();
+        table.sort_unstable();
+        table.dedup_by_key(|&mut (unicode, _)| unicode);
+        table
+    })
+}
@@ -0,0 +1,221 @@
+//! Coordinate space of extraction results.
+//!
+//! Extraction works in PDF user space: origin at the lower left, y up, `/Rotate` and the
+//! CropBox ignored. `coords="page"` maps results through [`PageGeometry`] onto the page as it
+//! is displayed (cropped, rotated, origin at the top left, in points), and `coords="pixels"`
+//! additionally scales by `dpi / 72` so boxes line up with a page rendered at that DPI, as in
+//! `extract_region_images`. Lengths (font sizes, line widths and dash patterns) are scaled by
+//! the same factor. Mapping happens after all analysis, which relies on user space.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+use crate::search::SearchHit;
+use crate::shapes::PathShape;
+use crate::structure::MarkedContentItem;
+use crate::tables::Table;
+use crate::{
+    BBox, CaptionInfo, FinalTextLayout, ImageLayout, ObjectLayout, PageGeometry, PathStyle,
+    PdfPage, PositionedImage, TextBlock, TextWord,
+};
+
+/// Mapping from user space to the coordinate space requested by the caller.
+pub struct OutputCoords {
+    /// `None` keeps user-space coordinates.
+    geometry: Option<PageGeometry>,
+}
+
+impl OutputCoords {
+    pub fn new(coords: &str, dpi: f32, page: &PdfPage) -> PyResult<Self> {
+        let scale = match coords {
+            "pdf" => return Ok(Self { geometry: None }),
+            "page" => 1.0,
+            "pixels" => {
+                if !dpi.is_finite() || dpi <= 0.0 {
+                    return Err(PyRuntimeError::new_err(
+                        "dpi must be a positive finite value",
+                    ));
+                }
+                f64::from(dpi) / 72.0
+            }
+            other => {
+                return Err(PyRuntimeError::new_err(format!(
+                    "coords must be 'pdf', 'page' or 'pixels', got '{}'",
+                    other
+                )));
+            }
+        };
+        Ok(Self {
+            geometry: Some(PageGeometry::from_pdf_page(page, scale)?),
+        })
+    }
+
+    pub fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        match &self.geometry {
+            Some(geometry) => {
+                let (px, py) = geometry.to_pixels(x as f64, y as f64);
+                (px as f32, py as f32)
+            }
+            None => (x, y),
+        }
+    }
+
+    /// Bounding box of the mapped corners, so `x0 <= x1` and `y0 <= y1` still hold.
+    pub fn bbox(&self, bbox: BBox) -> BBox {
+        if self.geometry.is_none() {
+            return bbox;
+        }
+        let corners = [
+            self.point(bbox.0, bbox.1),
+            self.point(bbox.2, bbox.1),
+            self.point(bbox.0, bbox.3),
+            self.point(bbox.2, bbox.3),
+        ];
+        corners.iter().fold(
+            (
+                f32::INFINITY,
+                f32::INFINITY,
+                f32::NEG_INFINITY,
+                f32::NEG_INFINITY,
+            ),
+            |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        )
+    }
+
+    /// A length such as a font size or line width, scaled like the coordinates.
+    pub fn length(&self, value: f32) -> f32 {
+        match &self.geometry {
+            Some(geometry) => value * geometry.scale as f32,
+            None => value,
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.geometry.is_none()
+    }
+
+    pub fn map_blocks(&self, blocks: &mut [TextBlock]) {
+        if self.is_identity() {
+            return;
+        }
+        for block in blocks {
+            (block.x0, block.y0, block.x1, block.y1) =
+                self.bbox((block.x0, block.y0, block.x1, block.y1));
+            (block.baseline_x, block.baseline_y) = self.point(block.baseline_x, block.baseline_y);
+            block.font_size = self.length(block.font_size);
+            for glyph in &mut block.glyphs {
+                (glyph.x0, glyph.y0, glyph.x1, glyph.y1) =
+                    self.bbox((glyph.x0, glyph.y0, glyph.x1, glyph.y1));
+                (glyph.origin_x, glyph.origin_y) = self.point(glyph.origin_x, glyph.origin_y);
+                glyph.font_size = self.length(glyph.font_size);
+            }
+        }
+    }
+
+    pub fn map_words(&self, words: &mut [TextWord]) {
+        for word in words {
+            word.bbox = self.bbox(word.bbox);
+            (word.origin_x, word.origin_y) = self.point(word.origin_x, word.origin_y);
+            word.font_size = self.length(word.font_size);
+        }
+    }
+
+    pub fn map_images(&self, images: &mut [PositionedImage]) {
+        for image in images {
+            (image.x0, image.y0, image.x1, image.y1) =
+                self.bbox((image.x0, image.y0, image.x1, image.y1));
+        }
+    }
+
+    pub fn map_segments(&self, segments: &mut [(String, Vec<(f32, f32)>)]) {
+        if self.is_identity() {
+            return;
+        }
+        for (_, points) in segments {
+            for point in points {
+                *point = self.point(point.0, point.1);
+            }
+        }
+    }
+
+    pub fn map_shapes(&self, shapes: &mut [PathShape]) {
+        if self.is_identity() {
+            return;
+        }
+        for shape in shapes {
+            shape.map_points(|(x, y)| self.point(x, y));
+            self.map_path_style(&mut shape.style);
+        }
+    }
+
+    pub fn map_path_styles(&self, styles: &mut [PathStyle]) {
+        if self.is_identity() {
+            return;
+        }
+        for style in styles {
+            self.map_path_style(style);
+        }
+    }
+
+    /// Scale the line width and dash pattern, and with them `effective_line_width`.
+    fn map_path_style(&self, style: &mut PathStyle) {
+        style.line_width = self.length(style.line_width);
+        for length in &mut style.dash_array {
+            *length = self.length(*length);
+        }
+        style.dash_phase = self.length(style.dash_phase);
+    }
+
+    pub fn map_tables(&self, tables: &mut [Table]) {
+        for table in tables {
+            table.bbox = self.bbox(table.bbox);
+            for cell in &mut table.cells {
+                cell.bbox = self.bbox(cell.bbox);
+            }
+            self.map_captions(&mut table.captions);
+        }
+    }
+
+    pub fn map_search_hits(&self, hits: &mut [SearchHit]) {
+        for hit in hits {
+            for rect in &mut hit.rects {
+                *rect = self.bbox(*rect);
+            }
+        }
+    }
+
+    pub fn map_text_layouts(&self, layouts: &mut [FinalTextLayout]) {
+        for layout in layouts {
+            layout.bbox = self.bbox(layout.bbox);
+            layout.font_size = self.length(layout.font_size);
+        }
+    }
+
+    pub fn map_image_layouts(&self, layouts: &mut [ImageLayout]) {
+        for layout in layouts {
+            layout.bbox = self.bbox(layout.bbox);
+            self.map_captions(&mut layout.captions);
+        }
+    }
+
+    pub fn map_object_layouts(&self, layouts: &mut [ObjectLayout]) {
+        for layout in layouts {
+            layout.bbox = self.bbox(layout.bbox);
+            self.map_captions(&mut layout.captions);
+        }
+    }
+
+    pub fn map_marked_content(&self, items: &mut HashMap<u32, MarkedContentItem>) {
+        for item in items.values_mut() {
+            item.bbox = item.bbox.map(|bbox| self.bbox(bbox));
+        }
+    }
+
+    fn map_captions(&self, captions: &mut [CaptionInfo]) {
+        for caption in captions {
+            caption.bbox = self.bbox(caption.bbox);
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Single-byte encodings of simple fonts and glyph-name to Unicode resolution.
+//!
+//! Simple (Type1/TrueType/Type3) fonts without a `/ToUnicode` CMap select glyphs by name:
+//! the `/Encoding` base encoding gives a name for every code, `/Differences` overrides some of
+//! them, and the Adobe Glyph List maps the names to Unicode. The bundled glyph list covers the
+//! names used by the standard encodings, the Symbol font, Greek and Latin Extended-A; other
+//! names are resolved from the `uniXXXX`/`uXXXX` conventions and `_`-joined ligatures.
+
+/// Base encodings defined by the PDF specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinEncoding {
+    Standard,
+    WinAnsi,
+    MacRoman,
+    PdfDoc,
+}
+
+impl BuiltinEncoding {
+    /// Resolve an `/Encoding` or `/BaseEncoding` name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "StandardEncoding" => Some(Self::Standard),
+            "WinAnsiEncoding" => Some(Self::WinAnsi),
+            "MacRomanEncoding" => Some(Self::MacRoman),
+            "PDFDocEncoding" => Some(Self::PdfDoc),
+            _ => None,
+        }
+    }
+
+    fn table(self) -> &'static [&'static str; 256] {
+        match self {
+            Self::Standard => &STANDARD_ENCODING,
+            Self::WinAnsi => &WIN_ANSI_ENCODING,
+            Self::MacRoman => &MAC_ROMAN_ENCODING,
+            Self::PdfDoc => &PDF_DOC_ENCODING,
+        }
+    }
+
+    /// Glyph name assigned to `code`, if the encoding defines one.
+    pub fn glyph_name(self, code: u8) -> Option<&'static str> {
+        Some(self.table()[code as usize]).filter(|name| !name.is_empty())
+    }
+}
+
+/// Glyph names for every code of a simple font after `/Differences` have been applied.
+#[derive(Debug, Clone)]
+pub struct SimpleEncoding {
+    names: Vec<Option<String>>,
+}
+
+impl SimpleEncoding {
+    /// Start from the glyph names produced by `base` for each code.
+    pub fn new(base: impl Fn(u8) -> Option<&'static str>) -> Self {
+        Self {
+            names: (0..=255u8)
+                .map(|code| base(code).map(str::to_owned))
+                .collect(),
+        }
+    }
+
+    /// Override the glyph name of a single code, as listed in a `/Differences` array.
+    pub fn set_difference(&mut self, code: u32, name: &str) {
+        if let Some(slot) = self.names.get_mut(code as usize) {
+            *slot = Some(name.to_owned());
+        }
+    }
+
+    pub fn glyph_name(&self, code: u16) -> Option<&str> {
+        self.names.get(code as usize)?.as_deref()
+    }
+
+    pub fn unicode(&self, code: u16) -> Option<String> {
+        self.glyph_name(code).and_then(glyph_name_to_unicode)
+    }
+}
+
+/// Map a glyph name to Unicode following the Adobe Glyph List conventions.
+pub fn glyph_name_to_unicode(name: &str) -> Option<String> {
+    let name = name.split('.').next().unwrap_or("");
+    if name.is_empty() {
+        return None;
+    }
+    if name.contains('_') {
+        let parts: Option<Vec<String>> = name.split('_').map(glyph_name_to_unicode).collect();
+        return parts.map(|parts| parts.concat());
+    }
+    if let Ok(index) = GLYPH_LIST.binary_search_by(|(candidate, _)| (*candidate).cmp(name)) {
+        return Some(GLYPH_LIST[index].1.to_string());
+    }
+    if let Some(hex) = name.strip_prefix("uni") {
+        if !hex.is_empty() && hex.len() % 4 == 0 {
+            let chars: Option<String> = hex
+                .as_bytes()
+                .chunks(4)
+                .map(|chunk| {
+                    std::str::from_utf8(chunk)
+                        .ok()
+                        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                        .and_then(char::from_u32)
+                })
+                .collect();
+            if chars.is_some() {
+                return chars;
+            }
+        }
+    }
+    if let Some(hex) = name.strip_prefix('u') {
+        if (4..=6).contains(&hex.len()) {
+            if let Some(ch) = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                return Some(ch.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Adobe StandardEncoding; the built-in encoding of the Latin standard fonts.
+#[rustfmt::skip]
+const STANDARD_ENCODING: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quoteright",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "quoteleft", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "exclamdown", "cent", "sterling", "fraction", "yen", "florin", "section",
+    "currency", "quotesingle", "quotedblleft", "guillemotleft", "guilsinglleft", "guilsinglright", "fi", "fl",
+    "", "endash", "dagger", "daggerdbl", "periodcentered", "", "paragraph", "bullet",
+    "quotesinglbase", "quotedblbase", "quotedblright", "guillemotright", "ellipsis", "perthousand", "", "questiondown",
+    "", "grave", "acute", "circumflex", "tilde", "macron", "breve", "dotaccent",
+    "dieresis", "", "ring", "cedilla", "", "hungarumlaut", "ogonek", "caron",
+    "emdash", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "AE", "", "ordfeminine", "", "", "", "",
+    "Lslash", "Oslash", "OE", "ordmasculine", "", "", "", "",
+    "", "ae", "", "", "", "dotlessi", "", "",
+    "lslash", "oslash", "oe", "germandbls", "", "", "", "",
+];
+
+/// WinAnsiEncoding (Windows code page 1252).
+#[rustfmt::skip]
+const WIN_ANSI_ENCODING: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "grave", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "Euro", "", "quotesinglbase", "florin", "quotedblbase", "ellipsis", "dagger", "daggerdbl",
+    "circumflex", "perthousand", "Scaron", "guilsinglleft", "OE", "", "Zcaron", "",
+    "", "quoteleft", "quoteright", "quotedblleft", "quotedblright", "bullet", "endash", "emdash",
+    "tilde", "trademark", "scaron", "guilsinglright", "oe", "", "zcaron", "Ydieresis",
+    "space", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "dieresis", "copyright", "ordfeminine", "guillemotleft", "logicalnot", "hyphen", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "ordmasculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adieresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Edieresis", "Igrave", "Iacute", "Icircumflex", "Idieresis",
+    "Eth", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odieresis", "multiply",
+    "Oslash", "Ugrave", "Uacute", "Ucircumflex", "Udieresis", "Yacute", "Thorn", "germandbls",
+    "agrave", "aacute", "acircumflex", "atilde", "adieresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "edieresis", "igrave", "iacute", "icircumflex", "idieresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odieresis", "divide",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udieresis", "yacute", "thorn", "ydieresis",
+];
+
+/// MacRomanEncoding, including the Mac OS Roman symbols missing from the PDF table.
+#[rustfmt::skip]
+const MAC_ROMAN_ENCODING: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "grave", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis", "aacute",
+    "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute", "egrave",
+    "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde", "oacute",
+    "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex", "udieresis",
+    "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph", "germandbls",
+    "registered", "copyright", "trademark", "acute", "dieresis", "notequal", "AE", "Oslash",
+    "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu", "partialdiff", "summation",
+    "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega", "ae", "oslash",
+    "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal", "Delta", "guillemotleft",
+    "guillemotright", "ellipsis", "space", "Agrave", "Atilde", "Otilde", "OE", "oe",
+    "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft", "quoteright", "divide", "lozenge",
+    "ydieresis", "Ydieresis", "fraction", "currency", "guilsinglleft", "guilsinglright", "fi", "fl",
+    "daggerdbl", "periodcentered", "quotesinglbase", "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute",
+    "Edieresis", "Egrave", "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex",
+    "apple", "Ograve", "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde",
+    "macron", "breve", "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron",
+];
+
+/// PDFDocEncoding.
+#[rustfmt::skip]
+const PDF_DOC_ENCODING: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "breve", "caron", "circumflex", "dotaccent", "hungarumlaut", "ogonek", "ring", "tilde",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "grave", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "bullet", "dagger", "daggerdbl", "ellipsis", "emdash", "endash", "florin", "fraction",
+    "guilsinglleft", "guilsinglright", "minus", "perthousand", "quotedblbase", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "quotesinglbase", "trademark", "fi", "fl", "Lslash", "OE", "Scaron",
+    "Ydieresis", "Zcaron", "dotlessi", "lslash", "oe", "scaron", "zcaron", "",
+    "Euro", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "dieresis", "copyright", "ordfeminine", "guillemotleft", "logicalnot", "hyphen", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "ordmasculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adieresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Edieresis", "Igrave", "Iacute", "Icircumflex", "Idieresis",
+    "Eth", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odieresis", "multiply",
+    "Oslash", "Ugrave", "Uacute", "Ucircumflex", "Udieresis", "Yacute", "Thorn", "germandbls",
+    "agrave", "aacute", "acircumflex", "atilde", "adieresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "edieresis", "igrave", "iacute", "icircumflex", "idieresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odieresis", "divide",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udieresis", "yacute", "thorn", "ydieresis",
+];
+
+/// Glyph names from the Adobe Glyph List, sorted by name for binary search.
+#[rustfmt::skip]
+const GLYPH_LIST: &[(&str, char)] = &[
+    ("A", 'A'), ("AE", '\u{00C6}'), ("Aacute", '\u{00C1}'), ("Abreve", '\u{0102}'),
+    ("Acaron", '\u{01CD}'), ("Acircumflex", '\u{00C2}'), ("Adieresis", '\u{00C4}'),
+    ("Adotaccent", '\u{0226}'), ("Agrave", '\u{00C0}'), ("Alpha", '\u{0391}'),
+    ("Amacron", '\u{0100}'), ("Aogonek", '\u{0104}'), ("Aring", '\u{00C5}'),
+    ("Atilde", '\u{00C3}'), ("B", 'B'), ("Beta", '\u{0392}'), ("C", 'C'), ("Cacute", '\u{0106}'),
+    ("Ccaron", '\u{010C}'), ("Ccedilla", '\u{00C7}'), ("Ccircumflex", '\u{0108}'),
+    ("Cdotaccent", '\u{010A}'), ("Chi", '\u{03A7}'), ("D", 'D'), ("Dcaron", '\u{010E}'),
+    ("Dcroat", '\u{0110}'), ("Delta", '\u{0394}'), ("E", 'E'), ("Eacute", '\u{00C9}'),
+    ("Ebreve", '\u{0114}'), ("Ecaron", '\u{011A}'), ("Ecedilla", '\u{0228}'),
+    ("Ecircumflex", '\u{00CA}'), ("Edieresis", '\u{00CB}'), ("Edotaccent", '\u{0116}'),
+    ("Egrave", '\u{00C8}'), ("Emacron", '\u{0112}'), ("Eng", '\u{014A}'), ("Eogonek", '\u{0118}'),
+    ("Epsilon", '\u{0395}'), ("Eta", '\u{0397}'), ("Eth", '\u{00D0}'), ("Euro", '\u{20AC}'),
+    ("F", 'F'), ("G", 'G'), ("Gacute", '\u{01F4}'), ("Gamma", '\u{0393}'), ("Gbreve", '\u{011E}'),
+    ("Gcaron", '\u{01E6}'), ("Gcedilla", '\u{0122}'), ("Gcircumflex", '\u{011C}'),
+    ("Gcommaaccent", '\u{0122}'), ("Gdotaccent", '\u{0120}'), ("H", 'H'), ("Hbar", '\u{0126}'),
+    ("Hcaron", '\u{021E}'), ("Hcircumflex", '\u{0124}'), ("I", 'I'), ("IJ", '\u{0132}'),
+    ("Iacute", '\u{00CD}'), ("Ibreve", '\u{012C}'), ("Icaron", '\u{01CF}'),
+    ("Icircumflex", '\u{00CE}'), ("Idieresis", '\u{00CF}'), ("Idotaccent", '\u{0130}'),
+    ("Ifraktur", '\u{2111}'), ("Igrave", '\u{00CC}'), ("Imacron", '\u{012A}'),
+    ("Iogonek", '\u{012E}'), ("Iota", '\u{0399}'), ("Itilde", '\u{0128}'), ("J", 'J'),
+    ("Jcircumflex", '\u{0134}'), ("K", 'K'), ("Kappa", '\u{039A}'), ("Kcaron", '\u{01E8}'),
+    ("Kcedilla", '\u{0136}'), ("Kcommaaccent", '\u{0136}'), ("L", 'L'), ("Lacute", '\u{0139}'),
+    ("Lambda", '\u{039B}'), ("Lcaron", '\u{013D}'), ("Lcedilla", '\u{013B}'),
+    ("Lcommaaccent", '\u{013B}'), ("Ldot", '\u{013F}'), ("Lslash", '\u{0141}'), ("M", 'M'),
+    ("Mu", '\u{039C}'), ("N", 'N'), ("Nacute", '\u{0143}'), ("Ncaron", '\u{0147}'),
+    ("Ncedilla", '\u{0145}'), ("Ncommaaccent", '\u{0145}'), ("Ngrave", '\u{01F8}'),
+    ("Ntilde", '\u{00D1}'), ("Nu", '\u{039D}'), ("O", 'O'), ("OE", '\u{0152}'),
+    ("Oacute", '\u{00D3}'), ("Obreve", '\u{014E}'), ("Ocaron", '\u{01D1}'),
+    ("Ocircumflex", '\u{00D4}'), ("Odieresis", '\u{00D6}'), ("Odotaccent", '\u{022E}'),
+    ("Ograve", '\u{00D2}'), ("Ohungarumlaut", '\u{0150}'), ("Omacron", '\u{014C}'),
+    ("Omega", '\u{03A9}'), ("Omicron", '\u{039F}'), ("Oogonek", '\u{01EA}'),
+    ("Oslash", '\u{00D8}'), ("Otilde", '\u{00D5}'), ("P", 'P'), ("Phi", '\u{03A6}'),
+    ("Pi", '\u{03A0}'), ("Psi", '\u{03A8}'), ("Q", 'Q'), ("R", 'R'), ("Racute", '\u{0154}'),
+    ("Rcaron", '\u{0158}'), ("Rcedilla", '\u{0156}'), ("Rcommaaccent", '\u{0156}'),
+    ("Rfraktur", '\u{211C}'), ("Rho", '\u{03A1}'), ("S", 'S'), ("Sacute", '\u{015A}'),
+    ("Scaron", '\u{0160}'), ("Scedilla", '\u{015E}'), ("Scircumflex", '\u{015C}'),
+    ("Scommaaccent", '\u{0218}'), ("Sigma", '\u{03A3}'), ("T", 'T'), ("Tau", '\u{03A4}'),
+    ("Tbar", '\u{0166}'), ("Tcaron", '\u{0164}'), ("Tcedilla", '\u{0162}'),
+    ("Tcommaaccent", '\u{0162}'), ("Theta", '\u{0398}'), ("Thorn", '\u{00DE}'), ("U", 'U'),
+    ("Uacute", '\u{00DA}'), ("Ubreve", '\u{016C}'), ("Ucaron", '\u{01D3}'),
+    ("Ucircumflex", '\u{00DB}'), ("Udieresis", '\u{00DC}'), ("Ugrave", '\u{00D9}'),
+    ("Uhungarumlaut", '\u{0170}'), ("Umacron", '\u{016A}'), ("Uogonek", '\u{0172}'),
+    ("Upsilon", '\u{03A5}'), ("Uring", '\u{016E}'), ("Utilde", '\u{0168}'), ("V", 'V'), ("W", 'W'),
+    ("Wcircumflex", '\u{0174}'), ("X", 'X'), ("Xi", '\u{039E}'), ("Y", 'Y'),
+    ("Yacute", '\u{00DD}'), ("Ycircumflex", '\u{0176}'), ("Ydieresis", '\u{0178}'),
+    ("Ymacron", '\u{0232}'), ("Z", 'Z'), ("Zacute", '\u{0179}'), ("Zcaron", '\u{017D}'),
+    ("Zdotaccent", '\u{017B}'), ("Zeta", '\u{0396}'), ("a", 'a'), ("aacute", '\u{00E1}'),
+    ("abreve", '\u{0103}'), ("acaron", '\u{01CE}'), ("acircumflex", '\u{00E2}'),
+    ("acute", '\u{00B4}'), ("adieresis", '\u{00E4}'), ("adotaccent", '\u{0227}'),
+    ("ae", '\u{00E6}'), ("afii61289", '\u{2113}'), ("agrave", '\u{00E0}'), ("aleph", '\u{2135}'),
+    ("alpha", '\u{03B1}'), ("amacron", '\u{0101}'), ("ampersand", '&'), ("angle", '\u{2220}'),
+    ("aogonek", '\u{0105}'), ("apple", '\u{F8FF}'), ("approxequal", '\u{2248}'),
+    ("aring", '\u{00E5}'), ("arrowboth", '\u{2194}'), ("arrowdblboth", '\u{21D4}'),
+    ("arrowdblleft", '\u{21D0}'), ("arrowdblright", '\u{21D2}'), ("arrowdown", '\u{2193}'),
+    ("arrowleft", '\u{2190}'), ("arrowright", '\u{2192}'), ("arrowup", '\u{2191}'),
+    ("asciicircum", '^'), ("asciitilde", '~'), ("asterisk", '*'), ("asteriskmath", '\u{2217}'),
+    ("at", '@'), ("atilde", '\u{00E3}'), ("b", 'b'), ("backslash", '\\'), ("bar", '|'),
+    ("beta", '\u{03B2}'), ("braceleft", '{'), ("braceright", '}'), ("bracketleft", '['),
+    ("bracketright", ']'), ("breve", '\u{02D8}'), ("brokenbar", '\u{00A6}'),
+    ("bullet", '\u{2022}'), ("c", 'c'), ("cacute", '\u{0107}'), ("caron", '\u{02C7}'),
+    ("ccaron", '\u{010D}'), ("ccedilla", '\u{00E7}'), ("ccircumflex", '\u{0109}'),
+    ("cdotaccent", '\u{010B}'), ("cedilla", '\u{00B8}'), ("cent", '\u{00A2}'),
+    ("checkmark", '\u{2713}'), ("chi", '\u{03C7}'), ("circle", '\u{25CB}'),
+    ("circumflex", '\u{02C6}'), ("club", '\u{2663}'), ("colon", ':'), ("comma", ','),
+    ("congruent", '\u{2245}'), ("copyright", '\u{00A9}'), ("currency", '\u{00A4}'), ("d", 'd'),
+    ("dagger", '\u{2020}'), ("daggerdbl", '\u{2021}'), ("dcaron", '\u{010F}'),
+    ("dcroat", '\u{0111}'), ("degree", '\u{00B0}'), ("delta", '\u{03B4}'), ("diamond", '\u{2666}'),
+    ("dieresis", '\u{00A8}'), ("dieresistonos", '\u{0385}'), ("divide", '\u{00F7}'),
+    ("dollar", '$'), ("dotaccent", '\u{02D9}'), ("dotlessi", '\u{0131}'), ("dotmath", '\u{22C5}'),
+    ("e", 'e'), ("eacute", '\u{00E9}'), ("ebreve", '\u{0115}'), ("ecaron", '\u{011B}'),
+    ("ecedilla", '\u{0229}'), ("ecircumflex", '\u{00EA}'), ("edieresis", '\u{00EB}'),
+    ("edotaccent", '\u{0117}'), ("egrave", '\u{00E8}'), ("eight", '8'), ("element", '\u{2208}'),
+    ("ellipsis", '\u{2026}'), ("emacron", '\u{0113}'), ("emdash", '\u{2014}'),
+    ("emptyset", '\u{2205}'), ("endash", '\u{2013}'), ("eng", '\u{014B}'), ("eogonek", '\u{0119}'),
+    ("epsilon", '\u{03B5}'), ("equal", '='), ("equivalence", '\u{2261}'),
+    ("estimated", '\u{212E}'), ("eta", '\u{03B7}'), ("eth", '\u{00F0}'), ("exclam", '!'),
+    ("exclamdown", '\u{00A1}'), ("existential", '\u{2203}'), ("f", 'f'), ("ff", '\u{FB00}'),
+    ("ffi", '\u{FB03}'), ("ffl", '\u{FB04}'), ("fi", '\u{FB01}'), ("filledbox", '\u{25A0}'),
+    ("five", '5'), ("fiveeighths", '\u{215D}'), ("fl", '\u{FB02}'), ("florin", '\u{0192}'),
+    ("four", '4'), ("fraction", '\u{2044}'), ("g", 'g'), ("gacute", '\u{01F5}'),
+    ("gamma", '\u{03B3}'), ("gbreve", '\u{011F}'), ("gcaron", '\u{01E7}'),
+    ("gcedilla", '\u{0123}'), ("gcircumflex", '\u{011D}'), ("gcommaaccent", '\u{0123}'),
+    ("gdotaccent", '\u{0121}'), ("germandbls", '\u{00DF}'), ("gradient", '\u{2207}'),
+    ("grave", '`'), ("greater", '>'), ("greaterequal", '\u{2265}'), ("guillemotleft", '\u{00AB}'),
+    ("guillemotright", '\u{00BB}'), ("guilsinglleft", '\u{2039}'), ("guilsinglright", '\u{203A}'),
+    ("h", 'h'), ("hbar", '\u{0127}'), ("hcaron", '\u{021F}'), ("hcircumflex", '\u{0125}'),
+    ("heart", '\u{2665}'), ("hungarumlaut", '\u{02DD}'), ("hyphen", '-'), ("i", 'i'),
+    ("iacute", '\u{00ED}'), ("ibreve", '\u{012D}'), ("icaron", '\u{01D0}'),
+    ("icircumflex", '\u{00EE}'), ("idieresis", '\u{00EF}'), ("igrave", '\u{00EC}'),
+    ("ij", '\u{0133}'), ("imacron", '\u{012B}'), ("infinity", '\u{221E}'),
+    ("integral", '\u{222B}'), ("intersection", '\u{2229}'), ("iogonek", '\u{012F}'),
+    ("iota", '\u{03B9}'), ("itilde", '\u{0129}'), ("j", 'j'), ("jcaron", '\u{01F0}'),
+    ("jcircumflex", '\u{0135}'), ("k", 'k'), ("kappa", '\u{03BA}'), ("kcaron", '\u{01E9}'),
+    ("kcedilla", '\u{0137}'), ("kcommaaccent", '\u{0137}'), ("kgreenlandic", '\u{0138}'),
+    ("l", 'l'), ("lacute", '\u{013A}'), ("lambda", '\u{03BB}'), ("lcaron", '\u{013E}'),
+    ("lcedilla", '\u{013C}'), ("lcommaaccent", '\u{013C}'), ("ldot", '\u{0140}'), ("less", '<'),
+    ("lessequal", '\u{2264}'), ("logicaland", '\u{2227}'), ("logicalnot", '\u{00AC}'),
+    ("logicalor", '\u{2228}'), ("longs", '\u{017F}'), ("lozenge", '\u{25CA}'),
+    ("lslash", '\u{0142}'), ("m", 'm'), ("macron", '\u{00AF}'), ("middot", '\u{00B7}'),
+    ("minus", '\u{2212}'), ("minute", '\u{2032}'), ("mu", '\u{00B5}'), ("multiply", '\u{00D7}'),
+    ("n", 'n'), ("nacute", '\u{0144}'), ("napostrophe", '\u{0149}'), ("nbspace", '\u{00A0}'),
+    ("ncaron", '\u{0148}'), ("ncedilla", '\u{0146}'), ("ncommaaccent", '\u{0146}'),
+    ("ngrave", '\u{01F9}'), ("nine", '9'), ("notelement", '\u{2209}'), ("notequal", '\u{2260}'),
+    ("ntilde", '\u{00F1}'), ("nu", '\u{03BD}'), ("numbersign", '#'), ("o", 'o'),
+    ("oacute", '\u{00F3}'), ("obreve", '\u{014F}'), ("ocaron", '\u{01D2}'),
+    ("ocircumflex", '\u{00F4}'), ("odieresis", '\u{00F6}'), ("odotaccent", '\u{022F}'),
+    ("oe", '\u{0153}'), ("ogonek", '\u{02DB}'), ("ograve", '\u{00F2}'),
+    ("ohungarumlaut", '\u{0151}'), ("omacron", '\u{014D}'), ("omega", '\u{03C9}'),
+    ("omega1", '\u{03D6}'), ("omicron", '\u{03BF}'), ("one", '1'), ("oneeighth", '\u{215B}'),
+    ("onehalf", '\u{00BD}'), ("onequarter", '\u{00BC}'), ("onesuperior", '\u{00B9}'),
+    ("onethird", '\u{2153}'), ("oogonek", '\u{01EB}'), ("openbullet", '\u{25E6}'),
+    ("ordfeminine", '\u{00AA}'), ("ordmasculine", '\u{00BA}'), ("oslash", '\u{00F8}'),
+    ("otilde", '\u{00F5}'), ("p", 'p'), ("paragraph", '\u{00B6}'), ("parenleft", '('),
+    ("parenright", ')'), ("partialdiff", '\u{2202}'), ("percent", '%'), ("period", '.'),
+    ("periodcentered", '\u{00B7}'), ("perpendicular", '\u{22A5}'), ("perthousand", '\u{2030}'),
+    ("phi", '\u{03C6}'), ("phi1", '\u{03D5}'), ("pi", '\u{03C0}'), ("plus", '+'),
+    ("plusminus", '\u{00B1}'), ("prime", '\u{2032}'), ("product", '\u{220F}'),
+    ("propersubset", '\u{2282}'), ("propersuperset", '\u{2283}'), ("proportional", '\u{221D}'),
+    ("psi", '\u{03C8}'), ("q", 'q'), ("question", '?'), ("questiondown", '\u{00BF}'),
+    ("quotedbl", '"'), ("quotedblbase", '\u{201E}'), ("quotedblleft", '\u{201C}'),
+    ("quotedblright", '\u{201D}'), ("quoteleft", '\u{2018}'), ("quoteright", '\u{2019}'),
+    ("quotesinglbase", '\u{201A}'), ("quotesingle", '\''), ("r", 'r'), ("racute", '\u{0155}'),
+    ("radical", '\u{221A}'), ("radicalex", '\u{F8E5}'), ("rcaron", '\u{0159}'),
+    ("rcedilla", '\u{0157}'), ("rcommaaccent", '\u{0157}'), ("reflexsubset", '\u{2286}'),
+    ("reflexsuperset", '\u{2287}'), ("registered", '\u{00AE}'), ("rho", '\u{03C1}'),
+    ("ring", '\u{02DA}'), ("s", 's'), ("sacute", '\u{015B}'), ("scaron", '\u{0161}'),
+    ("scedilla", '\u{015F}'), ("scircumflex", '\u{015D}'), ("scommaaccent", '\u{0219}'),
+    ("second", '\u{2033}'), ("section", '\u{00A7}'), ("semicolon", ';'), ("seven", '7'),
+    ("seveneighths", '\u{215E}'), ("sfthyphen", '\u{00AD}'), ("sigma", '\u{03C3}'),
+    ("sigma1", '\u{03C2}'), ("similar", '\u{223C}'), ("six", '6'), ("slash", '/'), ("space", ' '),
+    ("spade", '\u{2660}'), ("sterling", '\u{00A3}'), ("suchthat", '\u{220B}'),
+    ("summation", '\u{2211}'), ("t", 't'), ("tau", '\u{03C4}'), ("tbar", '\u{0167}'),
+    ("tcaron", '\u{0165}'), ("tcedilla", '\u{0163}'), ("tcommaaccent", '\u{0163}'),
+    ("therefore", '\u{2234}'), ("theta", '\u{03B8}'), ("theta1", '\u{03D1}'),
+    ("thorn", '\u{00FE}'), ("three", '3'), ("threeeighths", '\u{215C}'),
+    ("threequarters", '\u{00BE}'), ("threesuperior", '\u{00B3}'), ("tilde", '\u{02DC}'),
+    ("tonos", '\u{0384}'), ("trademark", '\u{2122}'), ("triagdn", '\u{25BC}'),
+    ("triagup", '\u{25B2}'), ("two", '2'), ("twosuperior", '\u{00B2}'), ("twothirds", '\u{2154}'),
+    ("u", 'u'), ("uacute", '\u{00FA}'), ("ubreve", '\u{016D}'), ("ucaron", '\u{01D4}'),
+    ("ucircumflex", '\u{00FB}'), ("udieresis", '\u{00FC}'), ("ugrave", '\u{00F9}'),
+    ("uhungarumlaut", '\u{0171}'), ("umacron", '\u{016B}'), ("underscore", '_'),
+    ("union", '\u{222A}'), ("universal", '\u{2200}'), ("uogonek", '\u{0173}'),
+    ("upsilon", '\u{03C5}'), ("uring", '\u{016F}'), ("utilde", '\u{0169}'), ("v", 'v'), ("w", 'w'),
+    ("wcircumflex", '\u{0175}'), ("weierstrass", '\u{2118}'), ("x", 'x'), ("xi", '\u{03BE}'),
+    ("y", 'y'), ("yacute", '\u{00FD}'), ("ycircumflex", '\u{0177}'), ("ydieresis", '\u{00FF}'),
+    ("yen", '\u{00A5}'), ("ymacron", '\u{0233}'), ("z", 'z'), ("zacute", '\u{017A}'),
+    ("zcaron", '\u{017E}'), ("zdotaccent", '\u{017C}'), ("zero", '0'), ("zeta", '\u{03B6}'),
+];
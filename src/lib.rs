@@ -1,23 +1,51 @@
 pub mod ai;
+mod bidi;
+mod cmaps;
+mod coords;
+mod encodings;
+pub mod markdown;
+mod normalize;
+pub mod ocr_xml;
+mod search;
+mod shapes;
+mod standard_fonts;
+mod structure;
+pub mod svg;
+mod tables;
 
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::Path;
 
+use cmaps::PredefinedCMap;
+use coords::OutputCoords;
+use encodings::{BuiltinEncoding, SimpleEncoding};
 use nipdf::ObjectValueError;
 use nipdf::file::File as NipdfFile;
 use nipdf_render::{RenderOptionBuilder, render_page};
-use pdf::content::{Matrix, Op, Point, Rect, TextDrawAdjusted};
+use normalize::TextNormalizer;
+use pdf::content::{
+    Color, LineCap, LineJoin, Matrix, Op, Point, Rect, TextDrawAdjusted, TextMode, Winding,
+};
+use pdf::encoding::BaseEncoding;
 use pdf::error::PdfError;
 use pdf::file::{CachedFile, FileOptions};
-use pdf::font::{Font, FontData, FontDescriptor, ToUnicodeMap, Widths};
+use pdf::font::{CIDFont, Font, FontData, FontDescriptor, FontType, ToUnicodeMap, Widths};
 use pdf::object::Resolve;
-use pdf::object::{ColorSpace, ImageXObject, MaybeRef, Page as PdfPage, Resources, XObject};
-use pdf::primitive::PdfString;
+use pdf::object::{
+    ColorSpace, FormDict, FormXObject, GraphicsStateParameters, ImageXObject, MaybeRef,
+    Page as PdfPage, PlainRef, RcRef, Resources, XObject,
+};
+use pdf::primitive::{Name, PdfString, Primitive};
 use png::{BitDepth, ColorType, Encoder};
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PyDict, PyList, PyTuple};
+use shapes::{PathCommand, PathShape};
+use standard_fonts::StandardFont;
+use structure::{MarkedContentItem, MarkedContentStack, MarkedSpan, StructElement, StructKid};
+use tables::{PaintedSegment, Table, TableCell};
 
 use image::imageops::FilterType;
 use image::{DynamicImage, imageops};
@@ -54,10 +82,63 @@ struct TextBlock {
     y1: f32,
     baseline_x: f32,
     baseline_y: f32,
+    font_name: Option<String>,
+    font_size: f32,
+    font_flags: FontFlags,
+    /// `None` when the text is painted with a pattern.
+    fill_color: Option<(f32, f32, f32)>,
+    render_mode: u8,
+    glyphs: Vec<TextGlyph>,
+    /// MCID of the innermost marked-content sequence the text was shown in.
+    mcid: Option<u32>,
+    /// Index of the text-showing operator that drew the block, counted over the page and
+    /// its forms.
+    show_index: usize,
+}
+
+impl TextBlock {
+    /// Render mode 3 (invisible) never paints, as in OCR text layers. The `pdf` crate has no
+    /// variant for modes 6 and 7, so clip-only text (7) cannot be told apart.
+    fn is_invisible(&self) -> bool {
+        self.render_mode == 3
+    }
+}
+
+/// Selection of invisible text (render mode 3) applied to extracted blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InvisibleTextFilter {
+    Include,
+    Exclude,
+    Only,
+}
+
+impl InvisibleTextFilter {
+    fn parse(value: &str) -> PyResult<Self> {
+        match value {
+            "include" => Ok(Self::Include),
+            "exclude" => Ok(Self::Exclude),
+            "only" => Ok(Self::Only),
+            other => Err(PyRuntimeError::new_err(format!(
+                "invisible_text must be 'include', 'exclude' or 'only', got '{}'",
+                other
+            ))),
+        }
+    }
+
+    fn apply(self, blocks: &mut Vec<TextBlock>) {
+        match self {
+            Self::Include => {}
+            Self::Exclude => blocks.retain(|block| !block.is_invisible()),
+            Self::Only => blocks.retain(|block| block.is_invisible()),
+        }
+    }
 }
 
 /// Maintain the current text state while iterating over PDF text operators.
-#[derive(Debug)]
+///
+/// Everything except the text matrices belongs to the graphics state and is saved and
+/// restored by `q`/`Q`.
+#[derive(Debug, Clone)]
 struct TextState {
     current_font: Option<String>,
     font_size: f32,
@@ -68,6 +149,13 @@ struct TextState {
     text_rise: f32,
     text_matrix: Matrix,
     text_line_matrix: Matrix,
+    ctm: Matrix,
+    fill_space: ColorSpaceFamily,
+    /// `None` while the fill is a pattern or otherwise has no single color.
+    fill_color: Option<(f32, f32, f32)>,
+    render_mode: u8,
+    /// Text-showing operators seen so far. Like the text matrices, it is not restored.
+    shown: usize,
 }
 
 impl Default for TextState {
@@ -82,6 +170,11 @@ impl Default for TextState {
             text_rise: 0.0,
             text_matrix: Matrix::default(),
             text_line_matrix: Matrix::default(),
+            ctm: Matrix::default(),
+            fill_space: ColorSpaceFamily::Gray,
+            fill_color: Some((0.0, 0.0, 0.0)),
+            render_mode: 0,
+            shown: 0,
         }
     }
 }
@@ -122,6 +215,18 @@ impl TextState {
         self.text_rise = rise;
     }
 
+    /// Only modes 0 to 5 reach here: `TextMode` has no variant for 6 and 7.
+    fn set_render_mode(&mut self, mode: TextMode) {
+        self.render_mode = match mode {
+            TextMode::Fill => 0,
+            TextMode::Stroke => 1,
+            TextMode::FillThenStroke => 2,
+            TextMode::Invisible => 3,
+            TextMode::FillAndClip => 4,
+            TextMode::StrokeAndClip => 5,
+        };
+    }
+
     fn translate_line(&mut self, tx: f32, ty: f32) {
         let translation = Matrix {
             a: 1.0,
@@ -139,25 +244,102 @@ impl TextState {
         self.translate_line(0.0, -self.leading);
     }
 
-    fn translate_text(&mut self, tx: f32) {
+    fn translate_text(&mut self, tx: f32, ty: f32) {
         let translation = Matrix {
             a: 1.0,
             b: 0.0,
             c: 0.0,
             d: 1.0,
             e: tx,
-            f: 0.0,
+            f: ty,
         };
         self.text_matrix = multiply_matrix(&self.text_matrix, &translation);
     }
+
+    fn transform(&mut self, matrix: &Matrix) {
+        self.ctm = concat_matrix(&self.ctm, matrix);
+    }
+
+    fn set_fill_color(&mut self, color: &Color) {
+        self.fill_color = color_to_rgb(color, &mut self.fill_space);
+    }
+
+    fn set_fill_space(&mut self, space: ColorSpaceFamily) {
+        self.fill_color = space.initial_color();
+        self.fill_space = space;
+    }
+
+    fn restore(&mut self, saved: TextState) {
+        let text_matrix = self.text_matrix;
+        let text_line_matrix = self.text_line_matrix;
+        let shown = self.shown;
+        *self = saved;
+        self.text_matrix = text_matrix;
+        self.text_line_matrix = text_line_matrix;
+        self.shown = shown;
+    }
+}
+
+/// Style flags taken from the font descriptor, with the BaseFont name as a fallback hint.
+#[derive(Debug, Clone, Copy, Default)]
+struct FontFlags {
+    bold: bool,
+    italic: bool,
+    serif: bool,
+    fixed_pitch: bool,
+}
+
+const FLAG_FIXED_PITCH: u32 = 1 << 0;
+const FLAG_SERIF: u32 = 1 << 1;
+const FLAG_SYMBOLIC: u32 = 1 << 2;
+const FLAG_ITALIC: u32 = 1 << 6;
+const FLAG_FORCE_BOLD: u32 = 1 << 18;
+
+impl FontFlags {
+    fn resolve(
+        descriptor: Option<&FontDescriptor>,
+        standard: Option<&StandardFont>,
+        base_font: Option<&str>,
+    ) -> Self {
+        let bits = descriptor.map(|d| d.flags.bits()).unwrap_or(0);
+        let italic_angle = descriptor.map(|d| d.italic_angle).unwrap_or(0.0);
+        let name = base_font.unwrap_or("").to_lowercase();
+        let style = name.rsplit(['-', ',']).next().unwrap_or("");
+        Self {
+            bold: bits & FLAG_FORCE_BOLD != 0
+                || ["bold", "black", "heavy", "semibold", "demi"]
+                    .iter()
+                    .any(|hint| style.contains(hint)),
+            italic: bits & FLAG_ITALIC != 0
+                || italic_angle != 0.0
+                || style.contains("italic")
+                || style.contains("oblique"),
+            serif: bits & FLAG_SERIF != 0 || standard.is_some_and(|font| font.serif),
+            fixed_pitch: bits & FLAG_FIXED_PITCH != 0
+                || standard.is_some_and(|font| font.fixed_pitch),
+        }
+    }
 }
 
 /// Extract the available font information for a page resource.
 struct ResolvedFont {
     widths: Option<Widths>,
     to_unicode: Option<ToUnicodeMap>,
-    is_cid: bool,
+    /// CMap of a CID-keyed font; `None` for simple fonts.
+    cmap: Option<PredefinedCMap>,
+    /// Whether CIDs belong to the Adobe-Japan1 collection.
+    japan1: bool,
+    /// Vertical metrics, present when the CMap selects vertical writing.
+    vertical: Option<VerticalMetrics>,
     metrics: Option<(f32, f32)>,
+    base_font: Option<String>,
+    flags: FontFlags,
+    /// Built-in metrics used when a standard font omits `/Widths`.
+    standard: Option<&'static StandardFont>,
+    /// Glyph names of a simple font, used when `/ToUnicode` does not cover a code.
+    encoding: Option<SimpleEncoding>,
+    /// Glyph-space metrics of a Type3 font, which replace `widths` and `metrics`.
+    type3: Option<Type3Metrics>,
 }
 
 impl ResolvedFont {
@@ -167,29 +349,87 @@ impl ResolvedFont {
             Some(map) => Some(map?),
             None => None,
         };
-        let metrics = resolve_font_metrics(font);
+        let type3 = Type3Metrics::from_font(font, resolver)?;
+        let metrics = match &type3 {
+            Some(type3) => type3.metrics().or_else(|| resolve_font_metrics(font)),
+            None => resolve_font_metrics(font),
+        };
+        let cmap = resolve_cmap(font);
+        let japan1 = cid_font(font).is_some_and(is_japan1);
+        let vertical = match (cmap, cid_font(font)) {
+            (Some(cmap), Some(cid)) if cmap.is_vertical() => {
+                Some(VerticalMetrics::from_cid_font(cid, resolver)?)
+            }
+            _ => None,
+        };
+        let base_font = font
+            .name
+            .as_ref()
+            .map(|name| strip_subset_tag(name.as_str()));
+        let standard = standard_font(font);
+        let encoding = resolve_simple_encoding(font, standard);
+        let flags = FontFlags::resolve(
+            resolve_font_descriptor(font),
+            standard,
+            base_font.as_deref(),
+        );
         Ok(Self {
             widths,
             to_unicode,
-            is_cid: font.is_cid(),
+            cmap,
+            japan1,
+            vertical,
             metrics,
+            base_font,
+            flags,
+            standard,
+            encoding,
+            type3,
         })
     }
 
     fn decode(&self, text: &PdfString) -> DecodedText {
         let bytes = text.as_bytes();
-        if self.is_cid {
-            decode_cid(bytes, self.to_unicode.as_ref())
-        } else {
-            decode_simple(bytes, self.to_unicode.as_ref())
+        match self.cmap {
+            Some(cmap) => decode_cid(bytes, self.to_unicode.as_ref(), cmap, self.japan1),
+            None => decode_simple(bytes, self.to_unicode.as_ref(), self.encoding.as_ref()),
+        }
+    }
+
+    /// Glyph selector used for width lookups: the CID for CID-keyed fonts, otherwise the code.
+    fn glyph_id(&self, code: u16) -> u16 {
+        match self.cmap {
+            Some(cmap) => cmap.cid(code).unwrap_or(0),
+            None => code,
         }
     }
 
     fn glyph_width(&self, code: u16) -> f32 {
-        self.widths
-            .as_ref()
-            .map(|w| w.get(code as usize))
-            .unwrap_or(1000.0)
+        if let Some(type3) = &self.type3 {
+            return type3.glyph_width(code);
+        }
+        if let Some(widths) = &self.widths {
+            return widths.get(self.glyph_id(code) as usize);
+        }
+        match self.standard {
+            Some(standard) => self
+                .encoding
+                .as_ref()
+                .and_then(|encoding| encoding.glyph_name(code))
+                .and_then(|name| standard.glyph_width(name))
+                .unwrap_or_else(|| standard.code_width(code)),
+            None => 1000.0,
+        }
+    }
+
+    /// Vertical advance and position vector of a glyph, for fonts in vertical writing mode.
+    fn vertical_metric(&self, code: u16) -> Option<VerticalMetric> {
+        let vertical = self.vertical.as_ref()?;
+        Some(vertical.get(self.glyph_id(code), self.glyph_width(code)))
+    }
+
+    fn is_vertical(&self) -> bool {
+        self.vertical.is_some()
     }
 
     fn metrics(&self) -> Option<(f32, f32)> {
@@ -197,120 +437,597 @@ impl ResolvedFont {
     }
 }
 
+/// Vertical displacement `w1y` and position vector `(vx, vy)` of a glyph, in glyph space.
+#[derive(Debug, Clone, Copy)]
+struct VerticalMetric {
+    advance: f32,
+    position_x: f32,
+    position_y: f32,
+}
+
+/// Vertical metrics of a CID font from `/DW2` and `/W2`.
+#[derive(Debug, Clone)]
+struct VerticalMetrics {
+    default_position_y: f32,
+    default_advance: f32,
+    /// CID ranges with their `(w1y, vx, vy)` entries.
+    ranges: Vec<(u16, u16, [f32; 3])>,
+}
+
+impl Default for VerticalMetrics {
+    fn default() -> Self {
+        Self {
+            default_position_y: 880.0,
+            default_advance: -1000.0,
+            ranges: Vec::new(),
+        }
+    }
+}
+
+impl VerticalMetrics {
+    fn from_cid_font(font: &CIDFont, resolver: &impl Resolve) -> Result<Self, PdfError> {
+        let mut metrics = Self::default();
+        if let Some(dw2) = font._other.get("DW2") {
+            let values = resolve_primitive(dw2, resolver)?.into_array()?;
+            if let [position_y, advance] = values.as_slice() {
+                metrics.default_position_y = position_y.as_number()?;
+                metrics.default_advance = advance.as_number()?;
+            }
+        }
+        if let Some(w2) = font._other.get("W2") {
+            let values = resolve_primitive(w2, resolver)?.into_array()?;
+            metrics.ranges = parse_vertical_widths(&values)?;
+        }
+        Ok(metrics)
+    }
+
+    fn get(&self, cid: u16, width: f32) -> VerticalMetric {
+        match self
+            .ranges
+            .iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&cid))
+        {
+            Some(&(_, _, [advance, position_x, position_y])) => VerticalMetric {
+                advance,
+                position_x,
+                position_y,
+            },
+            None => VerticalMetric {
+                advance: self.default_advance,
+                position_x: width / 2.0,
+                position_y: self.default_position_y,
+            },
+        }
+    }
+}
+
+/// Parse a `/W2` array: `c [w1y vx vy ...]` lists and `c_first c_last w1y vx vy` ranges.
+fn parse_vertical_widths(values: &[Primitive]) -> Result<Vec<(u16, u16, [f32; 3])>, PdfError> {
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    // Entries naming a CID outside 0..=65535 are skipped.
+    while index + 1 < values.len() {
+        let first = u16::try_from(values[index].as_integer()?).ok();
+        if let Primitive::Array(entries) = &values[index + 1] {
+            if let Some(first) = first {
+                for (offset, entry) in entries.chunks_exact(3).enumerate() {
+                    let Some(cid) = u16::try_from(offset)
+                        .ok()
+                        .and_then(|offset| first.checked_add(offset))
+                    else {
+                        break;
+                    };
+                    ranges.push((cid, cid, vertical_entry(entry)?));
+                }
+            }
+            index += 2;
+        } else {
+            let Some(entry) = values.get(index + 2..index + 5) else {
+                break;
+            };
+            let last = u16::try_from(values[index + 1].as_integer()?).ok();
+            if let (Some(first), Some(last)) = (first, last) {
+                ranges.push((first, last, vertical_entry(entry)?));
+            }
+            index += 5;
+        }
+    }
+    Ok(ranges)
+}
+
+fn vertical_entry(entry: &[Primitive]) -> Result<[f32; 3], PdfError> {
+    Ok([
+        entry[0].as_number()?,
+        entry[1].as_number()?,
+        entry[2].as_number()?,
+    ])
+}
+
+/// Widths and bounding box of a Type3 font, whose glyph space is mapped to text space by
+/// `/FontMatrix` rather than the fixed 1/1000 scale of other fonts.
+#[derive(Debug, Clone)]
+struct Type3Metrics {
+    font_matrix: [f32; 6],
+    first_char: u16,
+    widths: Vec<f32>,
+    font_bbox: Option<BBox>,
+}
+
+impl Type3Metrics {
+    fn from_font(font: &Font, resolver: &impl Resolve) -> Result<Option<Self>, PdfError> {
+        let (FontType::Type3, FontData::Other(dict)) = (&font.subtype, &font.data) else {
+            return Ok(None);
+        };
+        let numbers = |key: &str| -> Result<Option<Vec<f32>>, PdfError> {
+            let Some(value) = dict.get(key) else {
+                return Ok(None);
+            };
+            resolve_primitive(value, resolver)?
+                .into_array()?
+                .iter()
+                .map(|item| resolve_primitive(item, resolver)?.as_number())
+                .collect::<Result<Vec<f32>, PdfError>>()
+                .map(Some)
+        };
+        let font_matrix = match numbers("FontMatrix")?.as_deref() {
+            Some(&[a, b, c, d, e, f]) => [a, b, c, d, e, f],
+            _ => [0.001, 0.0, 0.0, 0.001, 0.0, 0.0],
+        };
+        let first_char = match dict.get("FirstChar") {
+            Some(value) => resolve_primitive(value, resolver)?.as_integer()?.max(0) as u16,
+            None => 0,
+        };
+        let font_bbox = match numbers("FontBBox")?.as_deref() {
+            Some(&[x0, y0, x1, y1]) => Some((x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1))),
+            _ => None,
+        };
+        Ok(Some(Self {
+            font_matrix,
+            first_char,
+            widths: numbers("Widths")?.unwrap_or_default(),
+            font_bbox,
+        }))
+    }
+
+    /// Horizontal advance of a glyph in thousandths of text space, like `/Widths` of other fonts.
+    /// The advance `(w, 0)` is mapped through the whole `/FontMatrix`, so skewed, rotated and
+    /// mirrored matrices give the length of the advance.
+    fn glyph_width(&self, code: u16) -> f32 {
+        let width = code
+            .checked_sub(self.first_char)
+            .and_then(|index| self.widths.get(index as usize))
+            .copied()
+            .unwrap_or(0.0);
+        let [a, b, ..] = self.font_matrix;
+        width * a.hypot(b) * 1000.0
+    }
+
+    /// Ascent and descent in thousandths of text space: the vertical extent of the `/FontBBox`
+    /// corners mapped through `/FontMatrix`.
+    fn metrics(&self) -> Option<(f32, f32)> {
+        let (x0, y0, x1, y1) = self.font_bbox?;
+        let [_, b, _, d, _, f] = self.font_matrix;
+        let ys =
+            [(x0, y0), (x1, y0), (x0, y1), (x1, y1)].map(|(x, y)| (b * x + d * y + f) * 1000.0);
+        let top = ys.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let bottom = ys.iter().copied().fold(f32::INFINITY, f32::min);
+        (top > bottom).then_some((top, bottom))
+    }
+}
+
+fn resolve_primitive(
+    primitive: &Primitive,
+    resolver: &impl Resolve,
+) -> Result<Primitive, PdfError> {
+    match primitive {
+        Primitive::Reference(reference) => resolver.resolve(*reference),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Remove the `ABCDEF+` prefix that marks an embedded font subset.
+fn strip_subset_tag(name: &str) -> String {
+    match name.split_once('+') {
+        Some((tag, rest)) if tag.len() == 6 && tag.chars().all(|c| c.is_ascii_uppercase()) => {
+            rest.to_owned()
+        }
+        _ => name.to_owned(),
+    }
+}
+
 fn descriptor_metrics(descriptor: &FontDescriptor) -> (f32, f32) {
     let ascent = descriptor.ascent.unwrap_or(descriptor.font_bbox.top);
     let descent = descriptor.descent.unwrap_or(descriptor.font_bbox.bottom);
     (ascent, descent)
 }
 
-fn resolve_font_metrics(font: &Font) -> Option<(f32, f32)> {
+fn resolve_font_descriptor(font: &Font) -> Option<&FontDescriptor> {
     match &font.data {
         FontData::Type0(type0) => type0
             .descendant_fonts
-            .get(0)
-            .and_then(|descendant| resolve_font_metrics(descendant)),
-        FontData::Type1(info) | FontData::TrueType(info) => info
-            .font_descriptor
-            .as_ref()
-            .map(|descriptor| descriptor_metrics(descriptor)),
-        FontData::CIDFontType0(cid) | FontData::CIDFontType2(cid) => {
-            Some(descriptor_metrics(&cid.font_descriptor))
-        }
+            .first()
+            .and_then(|descendant| resolve_font_descriptor(descendant)),
+        FontData::Type1(info) | FontData::TrueType(info) => info.font_descriptor.as_ref(),
+        FontData::CIDFontType0(cid) | FontData::CIDFontType2(cid) => Some(&cid.font_descriptor),
+        _ => None,
+    }
+}
+
+/// Descendant CIDFont of a Type0 font.
+fn cid_font(font: &Font) -> Option<&CIDFont> {
+    match &font.data {
+        FontData::Type0(type0) => type0
+            .descendant_fonts
+            .first()
+            .and_then(|descendant| cid_font(descendant)),
+        FontData::CIDFontType0(cid) | FontData::CIDFontType2(cid) => Some(cid),
+        _ => None,
+    }
+}
+
+fn is_japan1(font: &CIDFont) -> bool {
+    font.system_info
+        .get("Ordering")
+        .and_then(|ordering| ordering.as_string().ok())
+        .is_some_and(|ordering| ordering.to_string_lossy() == "Japan1")
+}
+
+/// Predefined CMap of a CID-keyed font, defaulting to `Identity-H` for unknown or embedded CMaps.
+fn resolve_cmap(font: &Font) -> Option<PredefinedCMap> {
+    if !font.is_cid() {
+        return None;
+    }
+    let named = match font.encoding().map(|encoding| &encoding.base) {
+        Some(BaseEncoding::Other(name)) => PredefinedCMap::from_name(name.as_str()),
+        _ => None,
+    };
+    Some(named.unwrap_or(PredefinedCMap::IDENTITY_H))
+}
+
+/// Standard 14 font matching the BaseFont of a simple font, if any.
+fn standard_font(font: &Font) -> Option<&'static StandardFont> {
+    if font.is_cid() || matches!(font.subtype, FontType::Type3) {
+        return None;
+    }
+    let name = strip_subset_tag(font.name.as_ref()?.as_str());
+    standard_fonts::lookup(&name)
+}
+
+/// Build the code-to-glyph-name table of a simple font from `/Encoding` and `/Differences`.
+///
+/// Without an explicit base encoding, standard fonts use their built-in encoding and other
+/// non-symbolic fonts use StandardEncoding; symbolic fonts only get their `/Differences`.
+fn resolve_simple_encoding(
+    font: &Font,
+    standard: Option<&'static StandardFont>,
+) -> Option<SimpleEncoding> {
+    if font.is_cid() {
+        return None;
+    }
+    let encoding = font.encoding();
+    let base = match encoding.map(|encoding| &encoding.base) {
+        Some(BaseEncoding::StandardEncoding) => Some(BuiltinEncoding::Standard),
+        Some(BaseEncoding::WinAnsiEncoding) => Some(BuiltinEncoding::WinAnsi),
+        Some(BaseEncoding::MacRomanEncoding) => Some(BuiltinEncoding::MacRoman),
+        Some(BaseEncoding::Other(name)) => BuiltinEncoding::from_name(name.as_str()),
         _ => None,
+    };
+    let symbolic = resolve_font_descriptor(font)
+        .is_some_and(|descriptor| descriptor.flags.bits() & FLAG_SYMBOLIC != 0);
+    let has_differences = encoding.is_some_and(|encoding| !encoding.differences.is_empty());
+    let mut simple = match (base, standard) {
+        (Some(base), _) => SimpleEncoding::new(|code| base.glyph_name(code)),
+        (None, Some(standard)) => SimpleEncoding::new(|code| standard.builtin_glyph_name(code)),
+        (None, None) if !symbolic => {
+            SimpleEncoding::new(|code| BuiltinEncoding::Standard.glyph_name(code))
+        }
+        (None, None) if has_differences => SimpleEncoding::new(|_| None),
+        (None, None) => return None,
+    };
+    if let Some(encoding) = encoding {
+        for (code, name) in encoding.differences.iter() {
+            simple.set_difference(*code, name.as_str());
+        }
+    }
+    Some(simple)
+}
+
+fn resolve_font_metrics(font: &Font) -> Option<(f32, f32)> {
+    resolve_font_descriptor(font)
+        .map(descriptor_metrics)
+        .or_else(|| standard_font(font).map(|standard| (standard.ascent, standard.descent)))
+}
+
+/// Family of the current color space, which decides how the operands of `sc` / `scn` read.
+#[derive(Debug, Clone, PartialEq)]
+enum ColorSpaceFamily {
+    Gray,
+    Rgb,
+    Cmyk,
+    /// Separation and DeviceN: each operand is a colorant tint, where 1 is full ink.
+    Tint,
+    /// Indexed: the operand selects an entry of `palette`, a color in `base`.
+    Indexed {
+        base: Box<ColorSpaceFamily>,
+        palette: Vec<u8>,
+    },
+    /// Pattern: painted by a tiling or shading pattern, with no single color.
+    Pattern,
+    /// ICC-based, CIE-based and unresolved spaces, read by their number of operands.
+    Other,
+}
+
+impl ColorSpaceFamily {
+    /// Family of the space selected by `cs` / `CS`, looked up in `/ColorSpace` unless it is a
+    /// device space or `Pattern`.
+    fn from_name(name: &Name, resources: Option<&MaybeRef<Resources>>) -> Self {
+        match name.as_str() {
+            "DeviceGray" | "G" => ColorSpaceFamily::Gray,
+            "DeviceRGB" | "RGB" => ColorSpaceFamily::Rgb,
+            "DeviceCMYK" | "CMYK" => ColorSpaceFamily::Cmyk,
+            "Pattern" => ColorSpaceFamily::Pattern,
+            _ => resources
+                .and_then(|res| res.color_spaces.get(name))
+                .map(Self::from_space)
+                .unwrap_or(ColorSpaceFamily::Other),
+        }
+    }
+
+    fn from_space(space: &ColorSpace) -> Self {
+        match space {
+            ColorSpace::DeviceGray => ColorSpaceFamily::Gray,
+            ColorSpace::DeviceRGB => ColorSpaceFamily::Rgb,
+            ColorSpace::DeviceCMYK => ColorSpaceFamily::Cmyk,
+            ColorSpace::Separation { .. } | ColorSpace::DeviceN { .. } => ColorSpaceFamily::Tint,
+            ColorSpace::Indexed(base, _, lookup) => ColorSpaceFamily::Indexed {
+                base: Box::new(Self::from_space(base)),
+                palette: lookup.to_vec(),
+            },
+            ColorSpace::Pattern { .. } => ColorSpaceFamily::Pattern,
+            _ => ColorSpaceFamily::Other,
+        }
+    }
+
+    fn component_count(&self) -> Option<usize> {
+        match self {
+            ColorSpaceFamily::Gray => Some(1),
+            ColorSpaceFamily::Rgb => Some(3),
+            ColorSpaceFamily::Cmyk => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Color set by selecting the space: black, full ink, the first palette entry, or no
+    /// color for patterns.
+    fn initial_color(&self) -> Option<(f32, f32, f32)> {
+        match self {
+            ColorSpaceFamily::Tint => self.to_rgb(&[1.0]),
+            ColorSpaceFamily::Indexed { .. } => self.to_rgb(&[0.0]),
+            ColorSpaceFamily::Pattern => None,
+            _ => Some((0.0, 0.0, 0.0)),
+        }
+    }
+
+    fn to_rgb(&self, components: &[f32]) -> Option<(f32, f32, f32)> {
+        match (self, components) {
+            (ColorSpaceFamily::Pattern, _) => None,
+            (ColorSpaceFamily::Tint, tints) if !tints.is_empty() => {
+                // The tint transform is not evaluated: the strongest colorant is shown as
+                // gray ink, so full ink stays dark whatever the colorant.
+                let ink = tints.iter().fold(0.0_f32, |ink, &tint| ink.max(tint));
+                let level = 1.0 - ink.clamp(0.0, 1.0);
+                Some((level, level, level))
+            }
+            (ColorSpaceFamily::Indexed { base, palette }, [index]) => {
+                let count = base.component_count()?;
+                let start = index.max(0.0) as usize * count;
+                let entry = palette.get(start..start + count)?;
+                let components: Vec<f32> = entry
+                    .iter()
+                    .map(|&value| f32::from(value) / 255.0)
+                    .collect();
+                base.to_rgb(&components)
+            }
+            (ColorSpaceFamily::Gray | ColorSpaceFamily::Other, [gray]) => {
+                Some((*gray, *gray, *gray))
+            }
+            (ColorSpaceFamily::Rgb | ColorSpaceFamily::Other, [r, g, b]) => Some((*r, *g, *b)),
+            (ColorSpaceFamily::Cmyk | ColorSpaceFamily::Other, [c, m, y, k]) => Some((
+                (1.0 - c) * (1.0 - k),
+                (1.0 - m) * (1.0 - k),
+                (1.0 - y) * (1.0 - k),
+            )),
+            _ => None,
+        }
     }
 }
 
+/// RGB of the operands of a color operator read in `space`, or `None` when the color is
+/// unknown (patterns, or operands that do not fit the space). The device operators `g`, `rg`
+/// and `k` also select their device space.
+fn color_to_rgb(color: &Color, space: &mut ColorSpaceFamily) -> Option<(f32, f32, f32)> {
+    let components: Vec<f32> = match color {
+        Color::Gray(gray) => {
+            *space = ColorSpaceFamily::Gray;
+            vec![*gray]
+        }
+        Color::Rgb(rgb) => {
+            *space = ColorSpaceFamily::Rgb;
+            vec![rgb.red, rgb.green, rgb.blue]
+        }
+        Color::Cmyk(cmyk) => {
+            *space = ColorSpaceFamily::Cmyk;
+            vec![cmyk.cyan, cmyk.magenta, cmyk.yellow, cmyk.key]
+        }
+        Color::Other(values) => values
+            .iter()
+            .filter_map(|value| value.as_number().ok())
+            .collect(),
+    };
+    space.to_rgb(&components)
+}
+
 /// Text decoded from a PDF string along with the glyph identifiers used for width calculation.
 struct DecodedText {
     text: String,
     codes: Vec<u16>,
+    /// Unicode text produced by each entry of `codes`.
+    unicode: Vec<String>,
 }
 
-fn decode_simple(bytes: &[u8], map: Option<&ToUnicodeMap>) -> DecodedText {
-    let mut text = String::new();
-    let mut codes = Vec::with_capacity(bytes.len());
+impl DecodedText {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            text: String::new(),
+            codes: Vec::with_capacity(capacity),
+            unicode: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, code: u16, value: &str) {
+        self.text.push_str(value);
+        self.codes.push(code);
+        self.unicode.push(value.to_owned());
+    }
+
+    fn push_char(&mut self, code: u16, value: char) {
+        self.push(code, value.encode_utf8(&mut [0; 4]));
+    }
+}
+
+fn decode_simple(
+    bytes: &[u8],
+    map: Option<&ToUnicodeMap>,
+    encoding: Option<&SimpleEncoding>,
+) -> DecodedText {
+    let mut decoded = DecodedText::with_capacity(bytes.len());
     for &byte in bytes {
         let code = byte as u16;
-        codes.push(code);
-        if let Some(map) = map {
-            if let Some(value) = map.get(code) {
-                text.push_str(value);
-                continue;
-            }
+        if let Some(value) = map.and_then(|map| map.get(code)) {
+            decoded.push(code, value);
+        } else if let Some(value) = encoding.and_then(|encoding| encoding.unicode(code)) {
+            decoded.push(code, &value);
+        } else {
+            decoded.push_char(code, char::from_u32(code as u32).unwrap_or('\u{FFFD}'));
         }
-        text.push(char::from_u32(code as u32).unwrap_or('\u{FFFD}'));
     }
-    DecodedText { text, codes }
+    decoded
 }
 
-fn decode_cid(bytes: &[u8], map: Option<&ToUnicodeMap>) -> DecodedText {
-    let mut text = String::new();
-    let mut codes = Vec::with_capacity(bytes.len() / 2);
-    for chunk in bytes.chunks(2) {
-        if chunk.len() != 2 {
+/// Decode a CID-keyed string, falling back from `/ToUnicode` to the CMap's own characters and
+/// then to the Adobe-Japan1 collection.
+fn decode_cid(
+    bytes: &[u8],
+    map: Option<&ToUnicodeMap>,
+    cmap: PredefinedCMap,
+    japan1: bool,
+) -> DecodedText {
+    let mut decoded = DecodedText::with_capacity(bytes.len() / 2);
+    let mut rest = bytes;
+    while let Some((code, length)) = cmap.next_code(rest) {
+        rest = &rest[length..];
+        if let Some(value) = map.and_then(|map| map.get(code)) {
+            decoded.push(code, value);
             continue;
         }
-        let code = u16::from_be_bytes([chunk[0], chunk[1]]);
-        codes.push(code);
-        if let Some(map) = map {
-            if let Some(value) = map.get(code) {
-                text.push_str(value);
-                continue;
-            }
-        }
-        text.push(char::from_u32(code as u32).unwrap_or('\u{FFFD}'));
+        let value = match cmap.unicode(code) {
+            Some(value) => Some(value),
+            None if japan1 => cmap.cid(code).and_then(cmaps::japan1_unicode),
+            None if cmap.is_identity() => char::from_u32(code as u32),
+            None => None,
+        };
+        decoded.push_char(code, value.unwrap_or('\u{FFFD}'));
     }
-    DecodedText { text, codes }
+    decoded
 }
 
 fn fallback_decode(text: &PdfString) -> DecodedText {
-    DecodedText {
-        text: text.to_string_lossy(),
-        codes: text.as_bytes().iter().map(|&b| b as u16).collect(),
+    let bytes = text.as_bytes();
+    let mut decoded = DecodedText::with_capacity(bytes.len());
+    for &byte in bytes {
+        decoded.push_char(byte as u16, byte as char);
     }
+    decoded.text = text.to_string_lossy();
+    decoded
 }
 
-fn compute_text_displacement(font: Option<&ResolvedFont>, codes: &[u16], state: &TextState) -> f32 {
-    let mut total = 0.0;
-    for &code in codes {
-        let glyph_width = font.map(|f| f.glyph_width(code)).unwrap_or(1000.0);
-        let mut advance = (glyph_width / 1000.0) * state.font_size;
-        advance += state.char_spacing;
-        if code == 32 {
-            advance += state.word_spacing;
-        }
-        total += advance;
+/// Width of a glyph in text space, before character and word spacing are applied.
+fn glyph_extent(font: Option<&ResolvedFont>, code: u16, state: &TextState) -> f32 {
+    let glyph_width = font.map(|f| f.glyph_width(code)).unwrap_or(1000.0);
+    (glyph_width / 1000.0) * state.font_size * (state.horizontal_scale / 100.0)
+}
+
+/// Vertical distance the text position moves after showing a glyph in vertical writing mode.
+fn vertical_glyph_advance(metric: VerticalMetric, code: u16, state: &TextState) -> f32 {
+    let mut spacing = state.char_spacing;
+    if code == 32 {
+        spacing += state.word_spacing;
+    }
+    (metric.advance / 1000.0) * state.font_size + spacing
+}
+
+/// Distance the text position moves after showing a glyph, including spacing.
+fn glyph_advance(font: Option<&ResolvedFont>, code: u16, state: &TextState) -> f32 {
+    let mut spacing = state.char_spacing;
+    if code == 32 {
+        spacing += state.word_spacing;
     }
-    total * (state.horizontal_scale / 100.0)
+    glyph_extent(font, code, state) + spacing * (state.horizontal_scale / 100.0)
 }
 
 const DEFAULT_ASCENT: f32 = 800.0;
 const DEFAULT_DESCENT: f32 = -200.0;
 
-fn build_text_block(
-    state: &TextState,
+/// A single glyph shown by a text operator, positioned in user space.
+#[derive(Debug, Clone)]
+struct TextGlyph {
     text: String,
-    displacement: f32,
-    metrics: (f32, f32),
-) -> TextBlock {
+    code: u16,
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    origin_x: f32,
+    origin_y: f32,
+    font_size: f32,
+    /// Unit vector in user space along which the text advances.
+    direction: (f32, f32),
+}
+
+/// Matrix mapping text space to user space for the current state.
+fn text_render_matrix(state: &TextState) -> Matrix {
+    multiply_matrix(&state.text_matrix, &state.ctm)
+}
+
+/// Font size as it appears in user space, taking the text matrix and CTM scaling into account.
+fn effective_font_size(state: &TextState) -> f32 {
+    let matrix = text_render_matrix(state);
+    state.font_size * matrix.c.hypot(matrix.d)
+}
+
+/// Bounding box in user space of the text-space span `start..end` along the baseline.
+fn text_span_bbox(state: &TextState, start: f32, end: f32, metrics: (f32, f32)) -> BBox {
     let (raw_ascent, raw_descent) = metrics;
     let ascent = (raw_ascent / 1000.0) * state.font_size;
     let descent = (raw_descent / 1000.0) * state.font_size;
     let rise = state.text_rise;
-    let points = [
-        (0.0, rise),
-        (displacement, rise),
-        (0.0, ascent + rise),
-        (displacement, ascent + rise),
-        (0.0, descent + rise),
-        (displacement, descent + rise),
-    ];
+    text_rect_bbox(
+        state,
+        (start, descent.min(0.0) + rise, end, ascent.max(0.0) + rise),
+    )
+}
+
+/// Bounding box in user space of a rectangle given in text space.
+fn text_rect_bbox(state: &TextState, rect: BBox) -> BBox {
+    let (left, bottom, right, top) = rect;
+    let matrix = text_render_matrix(state);
+    let points = [(left, bottom), (right, bottom), (left, top), (right, top)];
     let mut min_x = f32::INFINITY;
     let mut max_x = f32::NEG_INFINITY;
     let mut min_y = f32::INFINITY;
     let mut max_y = f32::NEG_INFINITY;
     for &(x_offset, y_offset) in &points {
-        let (x, y) = apply_matrix(&state.text_matrix, (x_offset, y_offset));
+        let (x, y) = apply_matrix(&matrix, (x_offset, y_offset));
         if x < min_x {
             min_x = x;
         }
@@ -324,15 +1041,138 @@ fn build_text_block(
             max_y = y;
         }
     }
-    let (baseline_x, baseline_y) = apply_matrix(&state.text_matrix, (0.0, rise));
+    (min_x, min_y, max_x, max_y)
+}
+
+/// Bounding box of a glyph shown in vertical writing mode with its vertical origin at
+/// `offset` along the text-space y axis.
+fn vertical_glyph_bbox(
+    state: &TextState,
+    metric: VerticalMetric,
+    width: f32,
+    offset: f32,
+    metrics: (f32, f32),
+) -> BBox {
+    let scale = state.font_size / 1000.0;
+    let (ascent, descent) = metrics;
+    let left = -metric.position_x * scale;
+    let baseline = offset - metric.position_y * scale + state.text_rise;
+    text_rect_bbox(
+        state,
+        (
+            left,
+            baseline + descent * scale,
+            left + width * scale,
+            baseline + ascent * scale,
+        ),
+    )
+}
+
+/// Direction in user space of the text-space writing axis: +x, or -y for vertical writing.
+fn writing_direction(matrix: &Matrix, vertical: bool) -> (f32, f32) {
+    let (dx, dy) = if vertical {
+        (-matrix.c, -matrix.d)
+    } else {
+        (matrix.a, matrix.b)
+    };
+    let length = dx.hypot(dy);
+    if length > 0.0 {
+        (dx / length, dy / length)
+    } else {
+        (1.0, 0.0)
+    }
+}
+
+/// Position glyphs along the writing direction, returning them with the text-space
+/// displacement `(tx, ty)` of the whole string.
+fn build_text_glyphs(
+    state: &TextState,
+    font: Option<&ResolvedFont>,
+    decoded: &DecodedText,
+    metrics: (f32, f32),
+) -> (Vec<TextGlyph>, (f32, f32)) {
+    let matrix = text_render_matrix(state);
+    let font_size = effective_font_size(state);
+    let vertical = font.is_some_and(ResolvedFont::is_vertical);
+    let direction = writing_direction(&matrix, vertical);
+    let mut glyphs = Vec::with_capacity(decoded.codes.len());
+    let mut offset = 0.0;
+    for (&code, text) in decoded.codes.iter().zip(&decoded.unicode) {
+        let vertical_metric = font.and_then(|resolved| resolved.vertical_metric(code));
+        let ((x0, y0, x1, y1), (origin_x, origin_y), advance) = match vertical_metric {
+            Some(metric) => {
+                let width = font
+                    .map(|resolved| resolved.glyph_width(code))
+                    .unwrap_or(1000.0);
+                let bbox = vertical_glyph_bbox(state, metric, width, offset, metrics);
+                let origin = apply_matrix(&matrix, (0.0, offset + state.text_rise));
+                (bbox, origin, vertical_glyph_advance(metric, code, state))
+            }
+            None => {
+                let extent = glyph_extent(font, code, state);
+                let bbox = text_span_bbox(state, offset, offset + extent, metrics);
+                let origin = apply_matrix(&matrix, (offset, state.text_rise));
+                (bbox, origin, glyph_advance(font, code, state))
+            }
+        };
+        glyphs.push(TextGlyph {
+            text: text.clone(),
+            code,
+            x0,
+            y0,
+            x1,
+            y1,
+            origin_x,
+            origin_y,
+            font_size,
+            direction,
+        });
+        offset += advance;
+    }
+    let displacement = if vertical {
+        (0.0, offset)
+    } else {
+        (offset, 0.0)
+    };
+    (glyphs, displacement)
+}
+
+fn build_text_block(
+    state: &TextState,
+    font: Option<&ResolvedFont>,
+    text: String,
+    displacement: (f32, f32),
+    metrics: (f32, f32),
+    glyphs: Vec<TextGlyph>,
+) -> TextBlock {
+    let vertical = font.is_some_and(ResolvedFont::is_vertical);
+    let (x0, y0, x1, y1) = match glyphs.split_first() {
+        Some((first, rest)) if vertical => rest
+            .iter()
+            .fold((first.x0, first.y0, first.x1, first.y1), |bbox, glyph| {
+                bbox_union(bbox, (glyph.x0, glyph.y0, glyph.x1, glyph.y1))
+            }),
+        _ => text_span_bbox(state, 0.0, displacement.0, metrics),
+    };
+    let (baseline_x, baseline_y) = apply_matrix(&text_render_matrix(state), (0.0, state.text_rise));
     TextBlock {
         text,
-        x0: min_x,
-        y0: min_y,
-        x1: max_x,
-        y1: max_y,
+        x0,
+        y0,
+        x1,
+        y1,
         baseline_x,
         baseline_y,
+        font_name: font
+            .and_then(|resolved| resolved.base_font.clone())
+            .or_else(|| state.current_font.clone()),
+        font_size: effective_font_size(state),
+        font_flags: font.map(|resolved| resolved.flags).unwrap_or_default(),
+        fill_color: state.fill_color,
+        render_mode: state.render_mode,
+        glyphs,
+        mcid: None,
+        show_index: state.shown,
     }
 }
 
@@ -354,14 +1194,16 @@ fn apply_matrix(matrix: &Matrix, point: (f32, f32)) -> (f32, f32) {
     )
 }
 
+/// Concatenates `next` onto `current` the way `cm` does: `next` is applied
+/// to points first, then `current`.
 fn concat_matrix(current: &Matrix, next: &Matrix) -> Matrix {
     Matrix {
-        a: current.a * next.a + current.b * next.c,
-        b: current.a * next.b + current.b * next.d,
-        c: current.c * next.a + current.d * next.c,
-        d: current.c * next.b + current.d * next.d,
-        e: current.a * next.e + current.c * next.f + current.e,
-        f: current.b * next.e + current.d * next.f + current.f,
+        a: next.a * current.a + next.b * current.c,
+        b: next.a * current.b + next.b * current.d,
+        c: next.c * current.a + next.d * current.c,
+        d: next.c * current.b + next.d * current.d,
+        e: next.e * current.a + next.f * current.c + current.e,
+        f: next.e * current.b + next.f * current.d + current.f,
     }
 }
 
@@ -394,11 +1236,11 @@ fn unit_square_bounds(matrix: &Matrix) -> (f32, f32, f32, f32) {
 }
 
 fn collect_fonts(
-    page: &PdfPage,
+    resources: Option<&MaybeRef<Resources>>,
     resolver: &impl Resolve,
 ) -> Result<HashMap<String, ResolvedFont>, PdfError> {
     let mut fonts = HashMap::new();
-    if let Ok(resources) = page.resources() {
+    if let Some(resources) = resources {
         for (name, font_ref) in resources.fonts.iter() {
             let resolved = ResolvedFont::from_font(font_ref, resolver)?;
             fonts.insert(name.as_str().to_owned(), resolved);
@@ -407,9 +1249,118 @@ fn collect_fonts(
     Ok(fonts)
 }
 
+/// Fonts of a content stream, backed by those of the stream that invoked it: a form may show
+/// text in a font selected by `Tf` before it was invoked.
+struct FontScope<'a> {
+    fonts: HashMap<String, ResolvedFont>,
+    parent: Option<&'a FontScope<'a>>,
+}
+
+impl FontScope<'_> {
+    fn get(&self, name: &str) -> Option<&ResolvedFont> {
+        self.fonts
+            .get(name)
+            .or_else(|| self.parent.and_then(|parent| parent.get(name)))
+    }
+}
+
+/// Maximum nesting depth followed when recursing into Form XObjects.
+const MAX_FORM_DEPTH: usize = 16;
+
+/// Form XObjects currently being walked, used to bound recursion and break cycles.
+#[derive(Default)]
+struct FormStack {
+    active: Vec<PlainRef>,
+}
+
+impl FormStack {
+    fn enter(&mut self, key: PlainRef) -> bool {
+        if self.active.len() >= MAX_FORM_DEPTH || self.active.contains(&key) {
+            return false;
+        }
+        self.active.push(key);
+        true
+    }
+
+    fn leave(&mut self) {
+        self.active.pop();
+    }
+
+    fn in_form(&self) -> bool {
+        !self.active.is_empty()
+    }
+}
+
+/// Content stream of a Form XObject, resolved against the resources it inherits.
+struct FormContent {
+    operations: Vec<Op>,
+    matrix: Matrix,
+    /// `/BBox` in form space, which clips everything the form draws.
+    bbox: BBox,
+    resources: Option<MaybeRef<Resources>>,
+}
+
+fn form_matrix(dict: &FormDict) -> Matrix {
+    let values: Vec<f32> = dict
+        .matrix
+        .as_ref()
+        .and_then(|matrix| matrix.as_array().ok())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_number().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    if values.len() != 6 {
+        return Matrix::default();
+    }
+    Matrix {
+        a: values[0],
+        b: values[1],
+        c: values[2],
+        d: values[3],
+        e: values[4],
+        f: values[5],
+    }
+}
+
+/// Content of a Form XObject, or `None` when its content stream cannot be loaded. A broken
+/// form is skipped so that the rest of the page is still extracted.
+fn form_content(
+    form: &FormXObject,
+    parent: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+) -> Option<FormContent> {
+    let dict = form.dict();
+    Some(FormContent {
+        operations: form.operations(resolver).ok()?,
+        matrix: form_matrix(dict),
+        bbox: (
+            dict.bbox.left.min(dict.bbox.right),
+            dict.bbox.bottom.min(dict.bbox.top),
+            dict.bbox.left.max(dict.bbox.right),
+            dict.bbox.bottom.max(dict.bbox.top),
+        ),
+        resources: dict.resources.clone().or_else(|| parent.cloned()),
+    })
+}
+
+/// The XObject named by `Do`, or `None` when it is missing or cannot be resolved. Like a
+/// broken form, an XObject that fails to load is skipped rather than failing the page.
+fn lookup_xobject(
+    name: &Name,
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+) -> Option<(PlainRef, RcRef<XObject>)> {
+    let xobject_ref = *resources?.xobjects.get(name)?;
+    let xobject = resolver.get(xobject_ref).ok()?;
+    Some((xobject_ref.get_inner(), xobject))
+}
+
 fn handle_text_draw(
     state: &mut TextState,
-    fonts: &HashMap<String, ResolvedFont>,
+    fonts: &FontScope,
     text: &PdfString,
     blocks: &mut Vec<TextBlock>,
 ) {
@@ -422,241 +1373,1305 @@ fn handle_text_draw(
     if decoded.text.is_empty() {
         return;
     }
-    let displacement = compute_text_displacement(font, &decoded.codes, state);
     let metrics = font
         .and_then(|resolved| resolved.metrics())
         .unwrap_or((DEFAULT_ASCENT, DEFAULT_DESCENT));
-    let block = build_text_block(state, decoded.text, displacement, metrics);
+    let (glyphs, displacement) = build_text_glyphs(state, font, &decoded, metrics);
+    let block = build_text_block(state, font, decoded.text, displacement, metrics, glyphs);
     blocks.push(block);
-    if displacement != 0.0 {
-        state.translate_text(displacement);
+    if displacement != (0.0, 0.0) {
+        state.translate_text(displacement.0, displacement.1);
+    }
+}
+
+fn handle_text_adjusted(
+    state: &mut TextState,
+    fonts: &FontScope,
+    array: &[TextDrawAdjusted],
+    blocks: &mut Vec<TextBlock>,
+) {
+    for item in array {
+        match item {
+            TextDrawAdjusted::Text(text) => handle_text_draw(state, fonts, text, blocks),
+            TextDrawAdjusted::Spacing(amount) => {
+                let vertical = state
+                    .current_font
+                    .as_ref()
+                    .and_then(|name| fonts.get(name))
+                    .is_some_and(ResolvedFont::is_vertical);
+                if vertical {
+                    let adjustment = -amount / 1000.0 * state.font_size;
+                    if adjustment != 0.0 {
+                        state.translate_text(0.0, adjustment);
+                    }
+                } else {
+                    let adjustment =
+                        -amount / 1000.0 * state.font_size * (state.horizontal_scale / 100.0);
+                    if adjustment != 0.0 {
+                        state.translate_text(adjustment, 0.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_text_blocks(
+    ops: &[Op],
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+) -> Result<Vec<TextBlock>, PdfError> {
+    let mut state = TextState::default();
+    let mut forms = FormStack::default();
+    let mut marked = MarkedContentStack::default();
+    let mut blocks = Vec::new();
+    let fonts = FontScope {
+        fonts: collect_fonts(resources, resolver)?,
+        parent: None,
+    };
+    walk_text_ops(
+        ops,
+        resources,
+        &fonts,
+        resolver,
+        &mut state,
+        &mut forms,
+        &mut marked,
+        &mut blocks,
+    )?;
+    Ok(blocks)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn walk_text_ops(
+    ops: &[Op],
+    resources: Option<&MaybeRef<Resources>>,
+    fonts: &FontScope,
+    resolver: &impl Resolve,
+    state: &mut TextState,
+    forms: &mut FormStack,
+    marked: &mut MarkedContentStack,
+    blocks: &mut Vec<TextBlock>,
+) -> Result<(), PdfError> {
+    let mut saved_states: Vec<TextState> = Vec::new();
+    for op in ops {
+        let first_new = blocks.len();
+        match op {
+            Op::Save => saved_states.push(state.clone()),
+            Op::Restore => {
+                if let Some(saved) = saved_states.pop() {
+                    state.restore(saved);
+                }
+            }
+            Op::Transform { matrix } => state.transform(matrix),
+            Op::FillColor { color } => state.set_fill_color(color),
+            Op::FillColorSpace { name } => {
+                state.set_fill_space(ColorSpaceFamily::from_name(name, resources))
+            }
+            Op::BeginText => state.begin_text(),
+            Op::EndText => {}
+            Op::SetTextMatrix { matrix } => state.set_text_matrix(*matrix),
+            Op::MoveTextPosition { translation } => {
+                state.translate_line(translation.x, translation.y)
+            }
+            Op::TextNewline => state.newline(),
+            Op::TextFont { name, size } => state.set_font(name.as_str(), *size),
+            Op::CharSpacing { char_space } => state.set_char_spacing(*char_space),
+            Op::WordSpacing { word_space } => state.set_word_spacing(*word_space),
+            Op::TextScaling { horiz_scale } => state.set_horizontal_scale(*horiz_scale),
+            Op::Leading { leading } => state.set_leading(*leading),
+            Op::TextRise { rise } => state.set_text_rise(*rise),
+            Op::TextRenderMode { mode } => state.set_render_mode(*mode),
+            Op::TextDraw { text } => {
+                handle_text_draw(state, fonts, text, blocks);
+                state.shown += 1;
+            }
+            Op::TextDrawAdjusted { array } => {
+                handle_text_adjusted(state, fonts, array, blocks);
+                state.shown += 1;
+            }
+            Op::BeginMarkedContent { properties, .. } => marked.begin(
+                properties.as_ref(),
+                resources,
+                resolver,
+                blocks.len(),
+                forms.in_form(),
+            ),
+            Op::EndMarkedContent => {
+                if let Some(MarkedSpan {
+                    actual_text: Some(actual_text),
+                    start,
+                    ..
+                }) = marked.end()
+                {
+                    replace_with_actual_text(blocks, start, actual_text);
+                }
+            }
+            Op::XObject { name } => {
+                if let Some((key, xobject)) = lookup_xobject(name, resources, resolver) {
+                    if let XObject::Form(form) = &*xobject {
+                        if forms.enter(key) {
+                            if let Some(content) = form_content(form, resources, resolver) {
+                                // A form whose fonts fail to load is skipped like a broken form.
+                                let form_fonts =
+                                    collect_fonts(content.resources.as_ref(), resolver);
+                                if let Ok(form_fonts) = form_fonts {
+                                    let form_fonts = FontScope {
+                                        fonts: form_fonts,
+                                        parent: Some(fonts),
+                                    };
+                                    let saved = state.clone();
+                                    state.transform(&content.matrix);
+                                    walk_text_ops(
+                                        &content.operations,
+                                        content.resources.as_ref(),
+                                        &form_fonts,
+                                        resolver,
+                                        state,
+                                        forms,
+                                        marked,
+                                        blocks,
+                                    )?;
+                                    state.restore(saved);
+                                }
+                            }
+                            forms.leave();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(mcid) = marked.mcid() {
+            for block in blocks.iter_mut().skip(first_new) {
+                block.mcid.get_or_insert(mcid);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replace the text blocks shown inside a marked-content sequence with its `/ActualText`.
+///
+/// The blocks from `start` on are merged into one block covering them all, with a single
+/// glyph carrying the replacement text.
+fn replace_with_actual_text(blocks: &mut Vec<TextBlock>, start: usize, actual_text: String) {
+    if start >= blocks.len() {
+        return;
+    }
+    let replaced: Vec<TextBlock> = blocks.drain(start + 1..).collect();
+    let block = &mut blocks[start];
+    for other in &replaced {
+        (block.x0, block.y0, block.x1, block.y1) =
+            bbox_union(bbox_from_block(block), bbox_from_block(other));
+    }
+    let mut glyphs = std::mem::take(&mut block.glyphs);
+    glyphs.extend(replaced.into_iter().flat_map(|other| other.glyphs));
+    if let Some(first) = glyphs.first() {
+        let mut glyph = first.clone();
+        for other in &glyphs[1..] {
+            (glyph.x0, glyph.y0, glyph.x1, glyph.y1) = bbox_union(
+                (glyph.x0, glyph.y0, glyph.x1, glyph.y1),
+                (other.x0, other.y0, other.x1, other.y1),
+            );
+        }
+        glyph.text = actual_text.clone();
+        block.glyphs = vec![glyph];
+    }
+    block.text = actual_text;
+}
+
+type BBox = (f32, f32, f32, f32);
+
+fn bbox_from_block(block: &TextBlock) -> BBox {
+    (block.x0, block.y0, block.x1, block.y1)
+}
+
+fn bbox_union(a: BBox, b: BBox) -> BBox {
+    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+}
+
+fn axis_gap(a_min: f32, a_max: f32, b_min: f32, b_max: f32) -> f32 {
+    if a_max < b_min {
+        b_min - a_max
+    } else if b_max < a_min {
+        a_min - b_max
+    } else {
+        0.0
+    }
+}
+
+fn bbox_close(a: BBox, b: BBox, horizontal_margin: f32, vertical_margin: f32) -> bool {
+    let x_gap = axis_gap(a.0, a.2, b.0, b.2);
+    let y_gap = axis_gap(a.1, a.3, b.1, b.3);
+    x_gap <= horizontal_margin && y_gap <= vertical_margin
+}
+
+fn bbox_gaps(a: BBox, b: BBox) -> (f32, f32) {
+    (axis_gap(a.0, a.2, b.0, b.2), axis_gap(a.1, a.3, b.1, b.3))
+}
+
+fn cmp_f32(a: f32, b: f32) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[derive(Clone)]
+struct TextLine {
+    center_y: f32,
+    text: String,
+    x0: f32,
+    font_size: f32,
+    bold: bool,
+    block_index: usize,
+}
+
+impl TextLine {
+    fn from_block(block: &TextBlock, block_index: usize) -> Self {
+        Self {
+            center_y: (block.y0 + block.y1) / 2.0,
+            text: bidi::logical_text(&block.glyphs).unwrap_or_else(|| block.text.clone()),
+            x0: block.x0,
+            font_size: block.font_size,
+            bold: block.font_flags.bold,
+            block_index,
+        }
+    }
+}
+
+struct GroupedTextLayout {
+    bbox: BBox,
+    lines: Vec<TextLine>,
+}
+
+struct FinalTextLayout {
+    bbox: BBox,
+    lines: Vec<String>,
+    /// Indices of the text blocks the layout was built from, from top to bottom.
+    block_indices: Vec<usize>,
+    combined: String,
+    is_caption: bool,
+    /// Position of the layout in reading order, see [`reading_order`].
+    reading_order: usize,
+    /// Character-weighted mean font size.
+    font_size: f32,
+    /// Whether most characters are set in a bold font.
+    bold: bool,
+    /// Whether lines after the first start further right, as in a hanging list item.
+    hanging_indent: bool,
+    role: LayoutRole,
+}
+
+/// Structural role of a text layout on the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayoutRole {
+    Title,
+    Heading(u8),
+    Paragraph,
+    ListItem,
+    Caption,
+    Footnote,
+    Header,
+    Footer,
+}
+
+impl LayoutRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            LayoutRole::Title => "title",
+            LayoutRole::Heading(_) => "heading",
+            LayoutRole::Paragraph => "paragraph",
+            LayoutRole::ListItem => "list_item",
+            LayoutRole::Caption => "caption",
+            LayoutRole::Footnote => "footnote",
+            LayoutRole::Header => "header",
+            LayoutRole::Footer => "footer",
+        }
+    }
+
+    fn heading_level(self) -> Option<u8> {
+        match self {
+            LayoutRole::Heading(level) => Some(level),
+            _ => None,
+        }
+    }
+}
+
+impl FinalTextLayout {
+    fn width(&self) -> f32 {
+        self.bbox.2 - self.bbox.0
+    }
+
+    fn height(&self) -> f32 {
+        self.bbox.3 - self.bbox.1
+    }
+}
+
+fn looks_like_caption(text: &str) -> bool {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+    let trimmed = trimmed
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(' || c == '[')
+        .trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+    if matches!(trimmed.chars().next(), Some('図') | Some('表')) {
+        return true;
+    }
+    let lower = trimmed.to_lowercase();
+    lower.starts_with("fig ")
+        || lower.starts_with("fig.")
+        || lower.starts_with("fig(")
+        || lower.starts_with("figure")
+        || lower.starts_with("table")
+}
+
+fn build_text_layouts(blocks: &[TextBlock]) -> Vec<FinalTextLayout> {
+    let mut sorted: Vec<(usize, &TextBlock)> = blocks.iter().enumerate().collect();
+    sorted.sort_by(|(_, a), (_, b)| {
+        let cmp_y = cmp_f32(b.y1, a.y1);
+        if cmp_y == Ordering::Equal {
+            cmp_f32(a.x0, b.x0)
+        } else {
+            cmp_y
+        }
+    });
+    let mut groups: Vec<GroupedTextLayout> = Vec::new();
+    for (block_index, block) in sorted {
+        let bbox = bbox_from_block(block);
+        let block_height = (block.y1 - block.y0).abs().max(1.0);
+        let horizontal_margin = block_height * 0.8 + 4.0;
+        let vertical_margin = block_height * 1.5 + 4.0;
+        if let Some(group) = groups
+            .iter_mut()
+            .find(|group| bbox_close(group.bbox, bbox, horizontal_margin, vertical_margin))
+        {
+            group.bbox = bbox_union(group.bbox, bbox);
+            group.lines.push(TextLine::from_block(block, block_index));
+        } else {
+            groups.push(GroupedTextLayout {
+                bbox,
+                lines: vec![TextLine::from_block(block, block_index)],
+            });
+        }
+    }
+
+    let mut layouts: Vec<FinalTextLayout> = groups
+        .into_iter()
+        .map(|mut group| {
+            group.lines.sort_by(|a, b| cmp_f32(b.center_y, a.center_y));
+            order_rtl_rows(&mut group.lines);
+            let (font_size, bold) = line_font_stats(&group.lines);
+            let hanging_indent = has_hanging_indent(&group.lines, font_size);
+            let block_indices = group.lines.iter().map(|line| line.block_index).collect();
+            let lines: Vec<String> = group.lines.into_iter().map(|line| line.text).collect();
+            let combined = lines.join("\n");
+            let first_line = lines.first().map(|s| s.as_str()).unwrap_or("");
+            let is_caption = looks_like_caption(first_line);
+            FinalTextLayout {
+                bbox: group.bbox,
+                lines,
+                block_indices,
+                combined,
+                is_caption,
+                reading_order: 0,
+                font_size,
+                bold,
+                hanging_indent,
+                role: LayoutRole::Paragraph,
+            }
+        })
+        .collect();
+
+    let boxes: Vec<BBox> = layouts.iter().map(|layout| layout.bbox).collect();
+    for (position, index) in reading_order(&boxes).into_iter().enumerate() {
+        layouts[index].reading_order = position;
+    }
+    layouts.sort_by_key(|layout| layout.reading_order);
+    classify_layouts(&mut layouts, blocks);
+    layouts
+}
+
+/// Order blocks sharing a baseline right to left when the row is mostly right-to-left text.
+///
+/// `lines` must already be sorted from top to bottom.
+fn order_rtl_rows(lines: &mut [TextLine]) {
+    let mut start = 0;
+    while start < lines.len() {
+        let tolerance = lines[start].font_size.abs().max(1.0) * 0.5;
+        let center_y = lines[start].center_y;
+        let end = start
+            + lines[start..]
+                .iter()
+                .take_while(|line| (line.center_y - center_y).abs() <= tolerance)
+                .count();
+        let row = &mut lines[start..end];
+        let text: String = row.iter().map(|line| line.text.as_str()).collect();
+        if row.len() > 1 && bidi::is_rtl_dominant(&text) {
+            row.sort_by(|a, b| cmp_f32(b.x0, a.x0));
+        }
+        start = end;
+    }
+}
+
+/// Whether two horizontal text blocks sit on the same row; vertical blocks are lines of
+/// their own.
+fn same_row(a: &TextBlock, b: &TextBlock) -> bool {
+    let horizontal = |block: &TextBlock| {
+        block
+            .glyphs
+            .first()
+            .is_none_or(|glyph| glyph.direction.0.abs() >= glyph.direction.1.abs())
+    };
+    let tolerance = a.font_size.abs().max(1.0) * 0.5;
+    horizontal(a) && horizontal(b) && ((a.y0 + a.y1) / 2.0 - (b.y0 + b.y1) / 2.0).abs() <= tolerance
+}
+
+/// Character-weighted mean font size of the lines and whether most characters are bold.
+fn line_font_stats(lines: &[TextLine]) -> (f32, bool) {
+    let mut size_sum = 0.0;
+    let mut chars = 0usize;
+    let mut bold_chars = 0usize;
+    for line in lines {
+        let count = line.text.chars().filter(|c| !c.is_whitespace()).count();
+        size_sum += line.font_size * count as f32;
+        chars += count;
+        if line.bold {
+            bold_chars += count;
+        }
+    }
+    if chars == 0 {
+        let size = lines.first().map(|line| line.font_size).unwrap_or(0.0);
+        return (size, false);
+    }
+    (size_sum / chars as f32, bold_chars * 2 > chars)
+}
+
+/// Whether the second visual line starts noticeably to the right of the first one.
+///
+/// `lines` must be sorted top to bottom; fragments within half a font size of each other
+/// vertically belong to the same visual line.
+fn has_hanging_indent(lines: &[TextLine], font_size: f32) -> bool {
+    let tolerance = font_size.max(1.0) * 0.5;
+    let Some(first) = lines.first() else {
+        return false;
+    };
+    let first_x0 = lines
+        .iter()
+        .take_while(|line| (first.center_y - line.center_y).abs() <= tolerance)
+        .map(|line| line.x0)
+        .fold(f32::INFINITY, f32::min);
+    let Some(second) = lines
+        .iter()
+        .find(|line| first.center_y - line.center_y > tolerance)
+    else {
+        return false;
+    };
+    let second_x0 = lines
+        .iter()
+        .filter(|line| (second.center_y - line.center_y).abs() <= tolerance)
+        .map(|line| line.x0)
+        .fold(f32::INFINITY, f32::min);
+    second_x0 - first_x0 > font_size * 0.8
+}
+
+/// Font size, rounded to half a point, that covers the most characters on the page.
+fn body_font_size(blocks: &[TextBlock]) -> f32 {
+    let mut histogram: HashMap<i32, usize> = HashMap::new();
+    for block in blocks {
+        let count = block.text.chars().filter(|c| !c.is_whitespace()).count();
+        *histogram
+            .entry((block.font_size * 2.0).round() as i32)
+            .or_default() += count;
+    }
+    histogram
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(size, _)| size as f32 / 2.0)
+        .unwrap_or(0.0)
+}
+
+/// Whether text starts with a bullet or an enumeration marker such as `1.`, `(a)` or `①`.
+fn starts_with_list_marker(text: &str) -> bool {
+    let trimmed = text.trim_start();
+    let mut chars = trimmed.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if matches!(
+        first,
+        '•' | '・' | '●' | '○' | '◦' | '▪' | '■' | '□' | '◆' | '◇' | '‣' | '※'
+    ) || ('\u{2460}'..='\u{2473}').contains(&first)
+    {
+        return true;
+    }
+    if matches!(first, '-' | '–' | '*') {
+        return chars.next().is_some_and(char::is_whitespace);
+    }
+    let (body, parenthesized) = match trimmed.strip_prefix(['(', '（']) {
+        Some(rest) => (rest, true),
+        None => (trimmed, false),
+    };
+    let digits = body.chars().take_while(char::is_ascii_digit).count();
+    let marker_len = if digits > 0 {
+        digits
+    } else {
+        usize::from(body.starts_with(|c: char| c.is_ascii_alphabetic()))
+    };
+    if marker_len == 0 || marker_len > 3 {
+        return false;
+    }
+    let rest = &body[marker_len..];
+    let closed = if parenthesized {
+        rest.strip_prefix([')', '）'])
+    } else {
+        rest.strip_prefix(['.', ')', '）', '．'])
+    };
+    closed.is_some_and(|rest| rest.starts_with(|c: char| c.is_whitespace() || !c.is_ascii()))
+}
+
+/// Whether text starts with a footnote marker: a number, `*`, `†`, `‡` or `注`.
+fn starts_with_footnote_marker(text: &str) -> bool {
+    text.trim_start()
+        .starts_with(|c: char| c.is_ascii_digit() || matches!(c, '*' | '†' | '‡' | '注'))
+}
+
+/// Share of the text extent, from the top and bottom, where running headers and footers live.
+const PAGE_MARGIN_RATIO: f32 = 0.06;
+/// Share of the text extent, from the bottom, where footnotes are looked for.
+const FOOTNOTE_REGION_RATIO: f32 = 0.3;
+/// Font size ratio to the body text above which a layout is a heading.
+const HEADING_SIZE_RATIO: f32 = 1.15;
+/// Font size ratio to the body text above which the largest text near the top is the title.
+const TITLE_SIZE_RATIO: f32 = 1.5;
+
+/// Assign a [`LayoutRole`] to every layout from page-wide font size statistics, position,
+/// emphasis and list or footnote markers.
+fn classify_layouts(layouts: &mut [FinalTextLayout], blocks: &[TextBlock]) {
+    let Some(extent) = layouts.iter().map(|layout| layout.bbox).reduce(bbox_union) else {
+        return;
+    };
+    let body_size = body_font_size(blocks);
+    let height = (extent.3 - extent.1).max(1.0);
+    let is_short = |layout: &FinalTextLayout| {
+        layout.lines.len() <= 2 && layout.combined.chars().count() <= 120
+    };
+    let isolated = |index: usize, layouts: &[FinalTextLayout]| {
+        let bbox = layouts[index].bbox;
+        layouts.iter().enumerate().all(|(other, layout)| {
+            other == index || axis_gap(bbox.1, bbox.3, layout.bbox.1, layout.bbox.3) >= body_size
+        })
+    };
+
+    let mut roles = vec![LayoutRole::Paragraph; layouts.len()];
+    let mut heading_sizes: Vec<f32> = Vec::new();
+    for (index, layout) in layouts.iter().enumerate() {
+        let (_, y0, _, y1) = layout.bbox;
+        let size = layout.font_size;
+        let not_larger = size <= body_size * 1.1;
+        roles[index] = if layout.is_caption {
+            LayoutRole::Caption
+        } else if y0 >= extent.3 - height * PAGE_MARGIN_RATIO
+            && is_short(layout)
+            && not_larger
+            && isolated(index, layouts)
+        {
+            LayoutRole::Header
+        } else if y1 <= extent.1 + height * PAGE_MARGIN_RATIO
+            && is_short(layout)
+            && not_larger
+            && isolated(index, layouts)
+        {
+            LayoutRole::Footer
+        } else if y1 <= extent.1 + height * FOOTNOTE_REGION_RATIO
+            && size < body_size * 0.9
+            && starts_with_footnote_marker(&layout.combined)
+        {
+            LayoutRole::Footnote
+        } else if is_short(layout)
+            && (size >= body_size * HEADING_SIZE_RATIO || layout.bold && size >= body_size * 0.95)
+        {
+            heading_sizes.push(size);
+            LayoutRole::Heading(0)
+        } else if starts_with_list_marker(&layout.combined) || layout.hanging_indent {
+            LayoutRole::ListItem
+        } else {
+            LayoutRole::Paragraph
+        };
+    }
+
+    // The largest heading in the upper half of the page is the title when it clearly stands out.
+    let title = layouts
+        .iter()
+        .enumerate()
+        .filter(|(index, layout)| {
+            roles[*index] == LayoutRole::Heading(0)
+                && layout.bbox.1 >= extent.1 + height * 0.5
+                && layout.font_size >= body_size * TITLE_SIZE_RATIO
+        })
+        .max_by(|a, b| cmp_f32(a.1.font_size, b.1.font_size));
+    if let Some((index, layout)) = title {
+        let title_size = layout.font_size;
+        let unique = heading_sizes
+            .iter()
+            .filter(|&&size| (size - title_size).abs() < 0.5)
+            .count()
+            == 1;
+        if unique {
+            roles[index] = LayoutRole::Title;
+            heading_sizes.retain(|&size| (size - title_size).abs() >= 0.5);
+        }
+    }
+
+    // Heading levels follow distinct sizes, largest first.
+    heading_sizes.sort_by(|a, b| cmp_f32(*b, *a));
+    heading_sizes.dedup_by(|a, b| (*a - *b).abs() < 0.5);
+    for (index, layout) in layouts.iter_mut().enumerate() {
+        layout.role = match roles[index] {
+            LayoutRole::Heading(_) => {
+                let rank = heading_sizes
+                    .iter()
+                    .position(|&size| (size - layout.font_size).abs() < 0.5)
+                    .unwrap_or(heading_sizes.len().saturating_sub(1));
+                LayoutRole::Heading((rank + 1).min(6) as u8)
+            }
+            role => role,
+        };
+    }
+}
+
+/// Minimum whitespace, in points, between regions separated by an XY-cut.
+const XY_CUT_MIN_GAP: f32 = 4.0;
+
+#[derive(Clone, Copy)]
+enum CutAxis {
+    /// Split into columns along the x axis, ordered left to right.
+    X,
+    /// Split into bands along the y axis, ordered top to bottom.
+    Y,
+}
+
+/// Order boxes for reading with a recursive XY-cut, returning their indices.
+///
+/// Column cuts are tried before band cuts so that the columns of a multi-column region are
+/// read one after the other; full-width headers and footers become bands of their own.
+fn reading_order(boxes: &[BBox]) -> Vec<usize> {
+    let mut order = Vec::with_capacity(boxes.len());
+    xy_cut(boxes, (0..boxes.len()).collect(), &mut order);
+    order
+}
+
+fn xy_cut(boxes: &[BBox], indices: Vec<usize>, order: &mut Vec<usize>) {
+    if indices.len() <= 1 {
+        order.extend(indices);
+        return;
+    }
+    if let Some(columns) = split_regions(boxes, &indices, CutAxis::X) {
+        for column in columns {
+            xy_cut(boxes, column, order);
+        }
+        return;
+    }
+    if let Some(bands) = split_regions(boxes, &indices, CutAxis::Y) {
+        // Consecutive multi-column bands are usually one column layout whose paragraphs happen
+        // to share a gap; cutting there would interleave the columns.
+        let mut merged: Vec<Vec<usize>> = Vec::new();
+        let mut previous_multi_column = false;
+        for band in &bands {
+            let multi_column = split_regions(boxes, band, CutAxis::X).is_some();
+            match merged.last_mut() {
+                Some(last) if multi_column && previous_multi_column => last.extend(band),
+                _ => merged.push(band.clone()),
+            }
+            previous_multi_column = multi_column;
+        }
+        let regions = if merged.len() > 1 { merged } else { bands };
+        for region in regions {
+            xy_cut(boxes, region, order);
+        }
+        return;
+    }
+    let mut remaining = indices;
+    remaining.sort_by(|&a, &b| {
+        let cmp_y = cmp_f32(boxes[b].3, boxes[a].3);
+        if cmp_y == Ordering::Equal {
+            cmp_f32(boxes[a].0, boxes[b].0)
+        } else {
+            cmp_y
+        }
+    });
+    order.extend(remaining);
+}
+
+/// Split boxes at whitespace gaps along `axis`, or `None` when no gap separates them.
+fn split_regions(boxes: &[BBox], indices: &[usize], axis: CutAxis) -> Option<Vec<Vec<usize>>> {
+    let interval = |index: usize| {
+        let (x0, y0, x1, y1) = boxes[index];
+        match axis {
+            CutAxis::X => (x0, x1),
+            CutAxis::Y => (-y1, -y0),
+        }
+    };
+    let mut sorted = indices.to_vec();
+    sorted.sort_by(|&a, &b| cmp_f32(interval(a).0, interval(b).0));
+    let mut regions: Vec<Vec<usize>> = Vec::new();
+    let mut end = f32::NEG_INFINITY;
+    for index in sorted {
+        let (start, stop) = interval(index);
+        match regions.last_mut() {
+            Some(region) if start <= end + XY_CUT_MIN_GAP => region.push(index),
+            _ => regions.push(vec![index]),
+        }
+        end = end.max(stop);
+    }
+    (regions.len() > 1).then_some(regions)
+}
+
+/// Gap between glyphs, relative to the font size, above which a space is inferred.
+const WORD_GAP_RATIO: f32 = 0.15;
+/// Offset across the writing direction, relative to the font size, tolerated on one baseline.
+const BASELINE_TOLERANCE_RATIO: f32 = 0.3;
+
+/// A word assembled from glyphs that share a baseline, possibly across several text blocks.
+#[derive(Debug, Clone)]
+struct TextWord {
+    text: String,
+    bbox: BBox,
+    origin_x: f32,
+    origin_y: f32,
+    font_name: Option<String>,
+    font_size: f32,
+    /// Index of the text block holding the first glyph.
+    block_index: usize,
+}
+
+impl TextWord {
+    fn start(glyph: &TextGlyph, block: &TextBlock, block_index: usize) -> Self {
+        Self {
+            text: glyph.text.clone(),
+            bbox: (glyph.x0, glyph.y0, glyph.x1, glyph.y1),
+            origin_x: glyph.origin_x,
+            origin_y: glyph.origin_y,
+            font_name: block.font_name.clone(),
+            font_size: glyph.font_size,
+            block_index,
+        }
+    }
+
+    fn push(&mut self, glyph: &TextGlyph) {
+        self.text.push_str(&glyph.text);
+        self.bbox = bbox_union(self.bbox, (glyph.x0, glyph.y0, glyph.x1, glyph.y1));
+        self.font_size = self.font_size.max(glyph.font_size);
+    }
+}
+
+/// Extent of a glyph's bounding box projected onto `direction`.
+fn project_glyph(glyph: &TextGlyph, direction: (f32, f32)) -> (f32, f32) {
+    let corners = [
+        (glyph.x0, glyph.y0),
+        (glyph.x1, glyph.y0),
+        (glyph.x0, glyph.y1),
+        (glyph.x1, glyph.y1),
+    ];
+    corners
+        .iter()
+        .map(|&(x, y)| x * direction.0 + y * direction.1)
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+/// Whether two glyphs advance in the same direction along one baseline.
+fn shares_baseline(previous: &TextGlyph, next: &TextGlyph) -> bool {
+    let direction = previous.direction;
+    let aligned = direction.0 * next.direction.0 + direction.1 * next.direction.1;
+    if aligned < 0.99 {
+        return false;
     }
+    let size = previous.font_size.max(next.font_size).max(f32::EPSILON);
+    let offset = (
+        next.origin_x - previous.origin_x,
+        next.origin_y - previous.origin_y,
+    );
+    let across = direction.0 * offset.1 - direction.1 * offset.0;
+    across.abs() <= size * BASELINE_TOLERANCE_RATIO
 }
 
-fn handle_text_adjusted(
-    state: &mut TextState,
-    fonts: &HashMap<String, ResolvedFont>,
-    array: &[TextDrawAdjusted],
-    blocks: &mut Vec<TextBlock>,
-) {
-    for item in array {
-        match item {
-            TextDrawAdjusted::Text(text) => handle_text_draw(state, fonts, text, blocks),
-            TextDrawAdjusted::Spacing(amount) => {
-                let adjustment =
-                    -amount / 1000.0 * state.font_size * (state.horizontal_scale / 100.0);
-                if adjustment != 0.0 {
-                    state.translate_text(adjustment);
-                }
-            }
-        }
+/// Whether `next` continues the word that ends with `previous`.
+///
+/// Glyph positions already include `TJ` adjustments, so a large negative spacing shows up as a
+/// gap here just like an explicit space would.
+fn continues_word(previous: &TextGlyph, next: &TextGlyph) -> bool {
+    if !shares_baseline(previous, next) {
+        return false;
     }
+    let direction = previous.direction;
+    let size = previous.font_size.max(next.font_size).max(f32::EPSILON);
+    let (_, previous_end) = project_glyph(previous, direction);
+    let (next_start, _) = project_glyph(next, direction);
+    let gap = next_start - previous_end;
+    gap <= size * WORD_GAP_RATIO && gap >= -size * 0.5
 }
 
-fn collect_text_blocks(ops: &[Op], fonts: &HashMap<String, ResolvedFont>) -> Vec<TextBlock> {
-    let mut state = TextState::default();
-    let mut blocks = Vec::new();
-    for op in ops {
-        match op {
-            Op::BeginText => state.begin_text(),
-            Op::EndText => {}
-            Op::SetTextMatrix { matrix } => state.set_text_matrix(*matrix),
-            Op::MoveTextPosition { translation } => {
-                state.translate_line(translation.x, translation.y)
+/// Group the glyphs of all text blocks into words, splitting on whitespace glyphs, gaps wider
+/// than [`WORD_GAP_RATIO`] of the font size and baseline changes.
+fn build_words(blocks: &[TextBlock]) -> Vec<TextWord> {
+    let mut words = Vec::new();
+    let mut current: Option<TextWord> = None;
+    let mut previous: Option<&TextGlyph> = None;
+    for (block_index, block) in blocks.iter().enumerate() {
+        for glyph in &block.glyphs {
+            if glyph.text.trim().is_empty() {
+                words.extend(current.take());
+                previous = None;
+                continue;
             }
-            Op::TextNewline => state.newline(),
-            Op::TextFont { name, size } => state.set_font(name.as_str(), *size),
-            Op::CharSpacing { char_space } => state.set_char_spacing(*char_space),
-            Op::WordSpacing { word_space } => state.set_word_spacing(*word_space),
-            Op::TextScaling { horiz_scale } => state.set_horizontal_scale(*horiz_scale),
-            Op::Leading { leading } => state.set_leading(*leading),
-            Op::TextRise { rise } => state.set_text_rise(*rise),
-            Op::TextDraw { text } => handle_text_draw(&mut state, fonts, text, &mut blocks),
-            Op::TextDrawAdjusted { array } => {
-                handle_text_adjusted(&mut state, fonts, array, &mut blocks)
+            match (current.as_mut(), previous) {
+                (Some(word), Some(last)) if continues_word(last, glyph) => word.push(glyph),
+                _ => {
+                    words.extend(current.take());
+                    current = Some(TextWord::start(glyph, block, block_index));
+                }
             }
-            _ => {}
+            previous = Some(glyph);
         }
     }
-    blocks
+    words.extend(current);
+    words
 }
 
-type BBox = (f32, f32, f32, f32);
+/// Segments of the stroked and filled paths with how each one was painted.
+fn collect_paths(
+    ops: &[Op],
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+) -> Result<Vec<PaintedSegment>, PdfError> {
+    let paths = collect_marked_paths(ops, resources, resolver)?;
+    Ok(paths
+        .segments
+        .into_iter()
+        .zip(paths.styles)
+        .filter(|(_, style)| style.paint.is_painted())
+        .map(|((kind, points), style)| (kind, points, style.paint))
+        .collect())
+}
 
-fn bbox_from_block(block: &TextBlock) -> BBox {
-    (block.x0, block.y0, block.x1, block.y1)
+/// How a path was painted, from its painting operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaintMode {
+    Stroke,
+    Fill,
+    FillAndStroke,
+    /// `W n`: the path only intersects the clipping path.
+    Clip,
+    /// `n` without a clip: the path has no effect.
+    NoOp,
 }
 
-fn bbox_union(a: BBox, b: BBox) -> BBox {
-    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+impl PaintMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            PaintMode::Stroke => "stroke",
+            PaintMode::Fill => "fill",
+            PaintMode::FillAndStroke => "fill_and_stroke",
+            PaintMode::Clip => "clip",
+            PaintMode::NoOp => "none",
+        }
+    }
+
+    fn is_painted(self) -> bool {
+        matches!(
+            self,
+            PaintMode::Stroke | PaintMode::Fill | PaintMode::FillAndStroke
+        )
+    }
+
+    fn fills(self) -> bool {
+        matches!(self, PaintMode::Fill | PaintMode::FillAndStroke)
+    }
+
+    fn strokes(self) -> bool {
+        matches!(self, PaintMode::Stroke | PaintMode::FillAndStroke)
+    }
 }
 
-fn axis_gap(a_min: f32, a_max: f32, b_min: f32, b_max: f32) -> f32 {
-    if a_max < b_min {
-        b_min - a_max
-    } else if b_max < a_min {
-        a_min - b_max
-    } else {
-        0.0
+/// Style of a path segment: the graphics state when it was painted.
+#[derive(Debug, Clone)]
+struct PathStyle {
+    stroke_space: ColorSpaceFamily,
+    fill_space: ColorSpaceFamily,
+    /// `None` while the color is a pattern or otherwise has no single color.
+    stroke_color: Option<(f32, f32, f32)>,
+    fill_color: Option<(f32, f32, f32)>,
+    line_width: f32,
+    line_cap: &'static str,
+    line_join: &'static str,
+    dash_array: Vec<f32>,
+    dash_phase: f32,
+    stroke_alpha: f32,
+    fill_alpha: f32,
+    /// `nonzero` or `evenodd` for filled and clipping paths, set by the painting operator.
+    fill_rule: Option<&'static str>,
+    paint: PaintMode,
+    /// Whether the path also intersects the clipping path (`W` or `W*`).
+    clip: bool,
+    /// Current transformation matrix, including the matrices of enclosing Form XObjects.
+    ctm: Matrix,
+    /// Index into `PathSink::clips` of the clipping path in effect, if any.
+    clip_path: Option<usize>,
+}
+
+impl Default for PathStyle {
+    fn default() -> Self {
+        Self {
+            stroke_space: ColorSpaceFamily::Gray,
+            fill_space: ColorSpaceFamily::Gray,
+            stroke_color: Some((0.0, 0.0, 0.0)),
+            fill_color: Some((0.0, 0.0, 0.0)),
+            line_width: 1.0,
+            line_cap: "butt",
+            line_join: "miter",
+            dash_array: Vec::new(),
+            dash_phase: 0.0,
+            stroke_alpha: 1.0,
+            fill_alpha: 1.0,
+            fill_rule: None,
+            paint: PaintMode::NoOp,
+            clip: false,
+            ctm: Matrix::default(),
+            clip_path: None,
+        }
     }
 }
 
-fn bbox_close(a: BBox, b: BBox, horizontal_margin: f32, vertical_margin: f32) -> bool {
-    let x_gap = axis_gap(a.0, a.2, b.0, b.2);
-    let y_gap = axis_gap(a.1, a.3, b.1, b.3);
-    x_gap <= horizontal_margin && y_gap <= vertical_margin
+impl PathStyle {
+    /// Mean scale of the CTM, used for lengths such as line widths and dash patterns.
+    fn ctm_scale(&self) -> f32 {
+        let Matrix { a, b, c, d, .. } = self.ctm;
+        (a * d - b * c).abs().sqrt()
+    }
+
+    /// Line width in user space of the page: `w` scaled by the mean scale of the CTM.
+    fn effective_line_width(&self) -> f32 {
+        self.line_width * self.ctm_scale()
+    }
+
+    /// Apply the entries of an `ExtGState` dictionary selected by `gs`.
+    fn apply_ext_gstate(&mut self, params: &GraphicsStateParameters) {
+        if let Some(width) = params.line_width {
+            self.line_width = width;
+        }
+        if let Some(alpha) = params.stroke_alpha {
+            self.stroke_alpha = alpha;
+        }
+        if let Some(alpha) = params.fill_alpha {
+            self.fill_alpha = alpha;
+        }
+        if let Some([array, phase]) = params.dash_pattern.as_deref() {
+            if let (Ok(array), Ok(phase)) = (array.as_array(), phase.as_number()) {
+                self.dash_array = array
+                    .iter()
+                    .filter_map(|value| value.as_number().ok())
+                    .collect();
+                self.dash_phase = phase;
+            }
+        }
+    }
 }
 
-fn bbox_gaps(a: BBox, b: BBox) -> (f32, f32) {
-    (axis_gap(a.0, a.2, b.0, b.2), axis_gap(a.1, a.3, b.1, b.3))
+fn line_cap_name(cap: LineCap) -> &'static str {
+    match cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    }
 }
 
-fn cmp_f32(a: f32, b: f32) -> Ordering {
-    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+fn line_join_name(join: LineJoin) -> &'static str {
+    match join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    }
 }
 
-#[derive(Clone)]
-struct TextLine {
-    center_y: f32,
-    text: String,
+fn winding_name(winding: &Winding) -> &'static str {
+    match winding {
+        Winding::NonZero => "nonzero",
+        Winding::EvenOdd => "evenodd",
+    }
 }
 
-struct GroupedTextLayout {
-    bbox: BBox,
-    lines: Vec<TextLine>,
+/// An image drawn by `Do` or an inline image, recorded when `PathSink::with_images` is set.
+struct PlacedImage {
+    image: ImageResult,
+    /// CTM mapping the unit square of the image into user space.
+    ctm: Matrix,
+    clip_path: Option<usize>,
+    /// Number of shapes painted before the image, which places it in the painting order.
+    order: usize,
 }
 
-struct FinalTextLayout {
-    bbox: BBox,
-    lines: Vec<String>,
-    combined: String,
-    is_caption: bool,
+/// Where a text-showing operator drew in the painting order, recorded for every `Tj` / `TJ`
+/// so that the text blocks it produced can be placed and clipped like the paths around them.
+struct PlacedText {
+    clip_path: Option<usize>,
+    /// Number of shapes painted before the text.
+    order: usize,
+    /// Number of images placed before the text, which orders it among images of equal `order`.
+    images: usize,
 }
 
-impl FinalTextLayout {
-    fn width(&self) -> f32 {
-        self.bbox.2 - self.bbox.0
+/// Path segments with the style and the MCID of the marked-content sequence each one was
+/// drawn in, and the painted paths as whole shapes.
+#[derive(Default)]
+struct PathSink {
+    segments: Vec<(String, Vec<(f32, f32)>)>,
+    styles: Vec<PathStyle>,
+    mcids: Vec<Option<u32>>,
+    shapes: Vec<PathShape>,
+    /// Clipping paths in the order they were set. Each one's `style.clip_path` is the clip
+    /// that was in effect when it was set, so the chain gives their intersection.
+    clips: Vec<PathShape>,
+    /// Whether to decode the images drawn on the page into `images`.
+    with_images: bool,
+    images: Vec<PlacedImage>,
+    /// One entry per text-showing operator, indexed like `TextBlock::show_index`.
+    texts: Vec<PlacedText>,
+    marked: MarkedContentStack,
+    /// Segments of the path under construction, kept until its painting operator.
+    pending: Vec<(String, Vec<(f32, f32)>, Option<u32>)>,
+    /// Construction commands of the same path.
+    commands: Vec<PathCommand>,
+    /// Winding rule of a `W` / `W*` seen since the path began.
+    clip: Option<&'static str>,
+}
+
+impl PathSink {
+    fn push(&mut self, kind: &str, points: Vec<(f32, f32)>) {
+        self.pending
+            .push((kind.to_string(), points, self.marked.mcid()));
     }
 
-    fn height(&self) -> f32 {
-        self.bbox.3 - self.bbox.1
+    fn command(&mut self, command: PathCommand) {
+        self.commands.push(command);
     }
-}
 
-fn looks_like_caption(text: &str) -> bool {
-    let trimmed = text.trim();
-    if trimmed.is_empty() {
-        return false;
+    /// Finish the current path at its painting operator, giving its segments `style`.
+    /// A path that is never painted is dropped. Returns the index of the new clipping path
+    /// when the path was also used with `W` or `W*`.
+    fn paint(
+        &mut self,
+        style: &PathStyle,
+        paint: PaintMode,
+        fill_rule: Option<&'static str>,
+    ) -> Option<usize> {
+        let clip = self.clip.take();
+        let (paint, fill_rule) = match (paint, clip) {
+            (PaintMode::NoOp, Some(winding)) => (PaintMode::Clip, Some(winding)),
+            _ => (paint, fill_rule),
+        };
+        let mut path_style = style.clone();
+        path_style.paint = paint;
+        path_style.fill_rule = fill_rule;
+        path_style.clip = clip.is_some();
+        for (kind, points, mcid) in self.pending.drain(..) {
+            self.segments.push((kind, points));
+            self.styles.push(path_style.clone());
+            self.mcids.push(mcid);
+        }
+        let commands = std::mem::take(&mut self.commands);
+        let new_clip = clip.map(|winding| {
+            let mut clip_style = path_style.clone();
+            clip_style.paint = PaintMode::Clip;
+            clip_style.fill_rule = Some(winding);
+            // An empty clipping path is kept: it hides everything drawn after it.
+            self.clips.push(PathShape::new(
+                commands.clone(),
+                clip_style,
+                self.marked.mcid(),
+            ));
+            self.clips.len() - 1
+        });
+        if paint.is_painted() {
+            let shape = PathShape::new(commands, path_style, self.marked.mcid());
+            if !shape.subpaths.is_empty() {
+                self.shapes.push(shape);
+            }
+        }
+        new_clip
     }
-    let trimmed = trimmed
-        .trim_start_matches(|c: char| c.is_whitespace() || c == '(' || c == '[')
-        .trim();
-    if trimmed.is_empty() {
-        return false;
+
+    /// Add the rectangle `bbox`, given in the space of `style.ctm`, as a clipping path inside
+    /// the clip of `style`, and return its index.
+    fn clip_to_bbox(&mut self, style: &PathStyle, (x0, y0, x1, y1): BBox) -> usize {
+        let [a, b, c, d] =
+            [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].map(|corner| apply_matrix(&style.ctm, corner));
+        let commands = vec![
+            PathCommand::MoveTo(a),
+            PathCommand::LineTo(b),
+            PathCommand::LineTo(c),
+            PathCommand::LineTo(d),
+            PathCommand::Close,
+        ];
+        let mut clip_style = style.clone();
+        clip_style.paint = PaintMode::Clip;
+        clip_style.fill_rule = Some("nonzero");
+        self.clips
+            .push(PathShape::new(commands, clip_style, self.marked.mcid()));
+        self.clips.len() - 1
     }
-    if matches!(trimmed.chars().next(), Some('図') | Some('表')) {
-        return true;
+
+    fn place_image(&mut self, style: &PathStyle, image: ImageResult) {
+        self.images.push(PlacedImage {
+            image,
+            ctm: style.ctm,
+            clip_path: style.clip_path,
+            order: self.shapes.len(),
+        });
     }
-    let lower = trimmed.to_lowercase();
-    lower.starts_with("fig ")
-        || lower.starts_with("fig.")
-        || lower.starts_with("fig(")
-        || lower.starts_with("figure")
-        || lower.starts_with("table")
-}
 
-fn build_text_layouts(blocks: &[TextBlock]) -> Vec<FinalTextLayout> {
-    let mut sorted: Vec<&TextBlock> = blocks.iter().collect();
-    sorted.sort_by(|a, b| {
-        let cmp_y = cmp_f32(b.y1, a.y1);
-        if cmp_y == Ordering::Equal {
-            cmp_f32(a.x0, b.x0)
-        } else {
-            cmp_y
-        }
-    });
-    let mut groups: Vec<GroupedTextLayout> = Vec::new();
-    for block in sorted {
-        let bbox = bbox_from_block(block);
-        let block_height = (block.y1 - block.y0).abs().max(1.0);
-        let horizontal_margin = block_height * 0.8 + 4.0;
-        let vertical_margin = block_height * 1.5 + 4.0;
-        if let Some(group) = groups
-            .iter_mut()
-            .find(|group| bbox_close(group.bbox, bbox, horizontal_margin, vertical_margin))
-        {
-            group.bbox = bbox_union(group.bbox, bbox);
-            group.lines.push(TextLine {
-                center_y: (block.y0 + block.y1) / 2.0,
-                text: block.text.clone(),
-            });
-        } else {
-            groups.push(GroupedTextLayout {
-                bbox,
-                lines: vec![TextLine {
-                    center_y: (block.y0 + block.y1) / 2.0,
-                    text: block.text.clone(),
-                }],
-            });
-        }
+    fn place_text(&mut self, style: &PathStyle) {
+        self.texts.push(PlacedText {
+            clip_path: style.clip_path,
+            order: self.shapes.len(),
+            images: self.images.len(),
+        });
     }
 
-    let mut layouts: Vec<FinalTextLayout> = groups
-        .into_iter()
-        .map(|mut group| {
-            group.lines.sort_by(|a, b| cmp_f32(b.center_y, a.center_y));
-            let lines: Vec<String> = group.lines.into_iter().map(|line| line.text).collect();
-            let combined = lines.join("\n");
-            let first_line = lines.first().map(|s| s.as_str()).unwrap_or("");
-            let is_caption = looks_like_caption(first_line);
-            FinalTextLayout {
-                bbox: group.bbox,
-                lines,
-                combined,
-                is_caption,
-            }
-        })
-        .collect();
+    /// Segments of the stroked and filled paths.
+    fn painted_segments(&self) -> Vec<(String, Vec<(f32, f32)>)> {
+        self.segments
+            .iter()
+            .zip(&self.styles)
+            .filter(|(_, style)| style.paint.is_painted())
+            .map(|(segment, _)| segment.clone())
+            .collect()
+    }
+}
 
-    layouts.sort_by(|a, b| {
-        let cmp_y = cmp_f32(b.bbox.3, a.bbox.3);
-        if cmp_y == Ordering::Equal {
-            cmp_f32(a.bbox.0, b.bbox.0)
-        } else {
-            cmp_y
-        }
-    });
-    layouts
+fn collect_marked_paths(
+    ops: &[Op],
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+) -> Result<PathSink, PdfError> {
+    let mut forms = FormStack::default();
+    let mut sink = PathSink::default();
+    walk_path_ops(
+        ops,
+        resources,
+        resolver,
+        PathStyle::default(),
+        &mut forms,
+        &mut sink,
+    )?;
+    Ok(sink)
 }
 
-fn collect_paths(ops: &[Op]) -> Vec<(String, Vec<(f32, f32)>)> {
-    let mut segments = Vec::new();
+fn walk_path_ops(
+    ops: &[Op],
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+    mut style: PathStyle,
+    forms: &mut FormStack,
+    sink: &mut PathSink,
+) -> Result<(), PdfError> {
+    let map = |ctm: &Matrix, point: &Point| apply_matrix(ctm, (point.x, point.y));
+    let mut saved: Vec<PathStyle> = Vec::new();
     let mut current_point: Option<Point> = None;
     let mut subpath_start: Option<Point> = None;
     for op in ops {
         match op {
+            Op::Save => saved.push(style.clone()),
+            Op::Restore => {
+                if let Some(previous) = saved.pop() {
+                    style = previous;
+                }
+            }
+            Op::Transform { matrix } => style.ctm = concat_matrix(&style.ctm, matrix),
+            Op::LineWidth { width } => style.line_width = *width,
+            Op::LineCap { cap } => style.line_cap = line_cap_name(*cap),
+            Op::LineJoin { join } => style.line_join = line_join_name(*join),
+            Op::Dash { pattern, phase } => {
+                style.dash_array = pattern.clone();
+                style.dash_phase = *phase;
+            }
+            Op::StrokeColor { color } => {
+                style.stroke_color = color_to_rgb(color, &mut style.stroke_space)
+            }
+            Op::FillColor { color } => {
+                style.fill_color = color_to_rgb(color, &mut style.fill_space)
+            }
+            Op::StrokeColorSpace { name } => {
+                style.stroke_space = ColorSpaceFamily::from_name(name, resources);
+                style.stroke_color = style.stroke_space.initial_color();
+            }
+            Op::FillColorSpace { name } => {
+                style.fill_space = ColorSpaceFamily::from_name(name, resources);
+                style.fill_color = style.fill_space.initial_color();
+            }
+            Op::GraphicsState { name } => {
+                if let Some(params) = resources.and_then(|res| res.graphics_states.get(name)) {
+                    style.apply_ext_gstate(params);
+                }
+            }
             Op::MoveTo { p } => {
+                sink.command(PathCommand::MoveTo(map(&style.ctm, p)));
                 current_point = Some(*p);
                 subpath_start = Some(*p);
             }
             Op::LineTo { p } => {
-                if let Some(start) = current_point {
-                    segments.push(("line".to_string(), vec![(start.x, start.y), (p.x, p.y)]));
+                let ctm = &style.ctm;
+                match current_point {
+                    Some(start) => {
+                        sink.push("line", vec![map(ctm, &start), map(ctm, p)]);
+                        sink.command(PathCommand::LineTo(map(ctm, p)));
+                    }
+                    // Without a current point the segment only starts a new subpath.
+                    None => {
+                        sink.command(PathCommand::MoveTo(map(ctm, p)));
+                        subpath_start = Some(*p);
+                    }
                 }
                 current_point = Some(*p);
             }
             Op::CurveTo { c1, c2, p } => {
-                if let Some(start) = current_point {
-                    segments.push((
-                        "curve".to_string(),
-                        vec![(start.x, start.y), (c1.x, c1.y), (c2.x, c2.y), (p.x, p.y)],
-                    ));
+                let ctm = &style.ctm;
+                match current_point {
+                    Some(start) => {
+                        sink.push(
+                            "curve",
+                            vec![map(ctm, &start), map(ctm, c1), map(ctm, c2), map(ctm, p)],
+                        );
+                        sink.command(PathCommand::CurveTo(
+                            map(ctm, c1),
+                            map(ctm, c2),
+                            map(ctm, p),
+                        ));
+                    }
+                    None => {
+                        sink.command(PathCommand::MoveTo(map(ctm, p)));
+                        subpath_start = Some(*p);
+                    }
                 }
                 current_point = Some(*p);
             }
             Op::Rect { rect } => {
                 let points = rect_to_points(rect);
-                segments.push(("rect".to_string(), points.clone()));
+                let mapped: Vec<(f32, f32)> = points
+                    .iter()
+                    .map(|&point| apply_matrix(&style.ctm, point))
+                    .collect();
+                if let [first, rest @ .., _] = mapped.as_slice() {
+                    sink.command(PathCommand::MoveTo(*first));
+                    for &point in rest {
+                        sink.command(PathCommand::LineTo(point));
+                    }
+                    sink.command(PathCommand::Close);
+                }
+                sink.push("rect", mapped);
                 if let Some(first) = points.first() {
                     current_point = Some(Point {
                         x: first.0,
@@ -667,17 +2682,88 @@ fn collect_paths(ops: &[Op]) -> Vec<(String, Vec<(f32, f32)>)> {
             }
             Op::Close => {
                 if let (Some(start), Some(first)) = (current_point, subpath_start) {
-                    segments.push((
-                        "line".to_string(),
-                        vec![(start.x, start.y), (first.x, first.y)],
-                    ));
+                    let ctm = &style.ctm;
+                    sink.push("line", vec![map(ctm, &start), map(ctm, &first)]);
+                    sink.command(PathCommand::Close);
                     current_point = Some(first);
                 }
             }
+            Op::Clip { winding } => sink.clip = Some(winding_name(winding)),
+            Op::Stroke | Op::Fill { .. } | Op::FillAndStroke { .. } | Op::EndPath => {
+                let (paint, fill_rule) = match op {
+                    Op::Stroke => (PaintMode::Stroke, None),
+                    Op::Fill { winding } => (PaintMode::Fill, Some(winding_name(winding))),
+                    Op::FillAndStroke { winding } => {
+                        (PaintMode::FillAndStroke, Some(winding_name(winding)))
+                    }
+                    _ => (PaintMode::NoOp, None),
+                };
+                // The new clip applies to what is drawn after the path, not to the path itself.
+                if let Some(clip) = sink.paint(&style, paint, fill_rule) {
+                    style.clip_path = Some(clip);
+                }
+                // Painting ends the path, leaving no current point.
+                current_point = None;
+                subpath_start = None;
+            }
+            Op::BeginMarkedContent { properties, .. } => {
+                let start = sink.segments.len();
+                let in_form = forms.in_form();
+                sink.marked
+                    .begin(properties.as_ref(), resources, resolver, start, in_form)
+            }
+            Op::EndMarkedContent => {
+                sink.marked.end();
+            }
+            Op::XObject { name } => {
+                if let Some((key, xobject)) = lookup_xobject(name, resources, resolver) {
+                    match &*xobject {
+                        XObject::Form(form) => {
+                            if forms.enter(key) {
+                                if let Some(content) = form_content(form, resources, resolver) {
+                                    let mut form_style = style.clone();
+                                    form_style.ctm = concat_matrix(&style.ctm, &content.matrix);
+                                    let clip = sink.clip_to_bbox(&form_style, content.bbox);
+                                    form_style.clip_path = Some(clip);
+                                    walk_path_ops(
+                                        &content.operations,
+                                        content.resources.as_ref(),
+                                        resolver,
+                                        form_style,
+                                        forms,
+                                        sink,
+                                    )?;
+                                }
+                                forms.leave();
+                            }
+                        }
+                        XObject::Image(image) if sink.with_images => {
+                            place_decoded_image(sink, &style, extract_image(image, resolver));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Op::InlineImage { image } if sink.with_images => {
+                place_decoded_image(sink, &style, extract_image(image, resolver));
+            }
+            Op::TextDraw { .. } | Op::TextDrawAdjusted { .. } => sink.place_text(&style),
             _ => {}
         }
     }
-    segments
+    Ok(())
+}
+
+/// Place an image unless it failed to decode. A broken image is skipped so that the rest of
+/// the page is still converted.
+fn place_decoded_image(
+    sink: &mut PathSink,
+    style: &PathStyle,
+    image: Result<ImageResult, PdfError>,
+) {
+    if let Ok(image) = image {
+        sink.place_image(style, image);
+    }
 }
 
 fn rect_to_points(rect: &Rect) -> Vec<(f32, f32)> {
@@ -706,6 +2792,7 @@ struct PositionedImage {
     x1: f32,
     y1: f32,
     image: ImageResult,
+    mcid: Option<u32>,
 }
 
 struct RegionImage {
@@ -734,10 +2821,38 @@ struct PageGeometry {
 
 impl PageGeometry {
     fn new(crop_box: &nipdf::file::Rectangle, rotation: i32, scale: f64) -> PyResult<Self> {
-        let left = crop_box.left_x as f64;
-        let lower = crop_box.lower_y as f64;
-        let right = crop_box.right_x as f64;
-        let upper = crop_box.upper_y as f64;
+        Self::from_bounds(
+            (
+                crop_box.left_x as f64,
+                crop_box.lower_y as f64,
+                crop_box.right_x as f64,
+                crop_box.upper_y as f64,
+            ),
+            rotation,
+            scale,
+        )
+    }
+
+    /// Geometry of a page opened with the `pdf` crate, using its CropBox (or MediaBox).
+    fn from_pdf_page(page: &PdfPage, scale: f64) -> PyResult<Self> {
+        let crop_box = page.crop_box().map_err(pdf_err)?;
+        Self::from_bounds(
+            (
+                crop_box.left.min(crop_box.right) as f64,
+                crop_box.bottom.min(crop_box.top) as f64,
+                crop_box.left.max(crop_box.right) as f64,
+                crop_box.bottom.max(crop_box.top) as f64,
+            ),
+            page.rotate,
+            scale,
+        )
+    }
+
+    fn from_bounds(
+        (left, lower, right, upper): (f64, f64, f64, f64),
+        rotation: i32,
+        scale: f64,
+    ) -> PyResult<Self> {
         let width = right - left;
         let height = upper - lower;
         if !width.is_finite() || !height.is_finite() || width <= 0.0 || height <= 0.0 {
@@ -817,6 +2932,11 @@ impl PageGeometry {
         let (tx, ty) = self.transform(x, y);
         (tx - self.min_x, ty - self.min_y)
     }
+
+    /// Width and height of the displayed page in output units.
+    fn pixel_size(&self) -> (f64, f64) {
+        (self.max_x - self.min_x, self.max_y - self.min_y)
+    }
 }
 
 fn collect_positioned_images(
@@ -824,20 +2944,55 @@ fn collect_positioned_images(
     resources: Option<&MaybeRef<Resources>>,
     resolver: &impl Resolve,
 ) -> Result<Vec<PositionedImage>, PdfError> {
-    let mut ctm = Matrix::default();
-    let mut stack: Vec<Matrix> = Vec::new();
+    let mut forms = FormStack::default();
+    let mut marked = MarkedContentStack::default();
     let mut images = Vec::new();
     let mut inline_index = 0usize;
+    walk_image_ops(
+        ops,
+        resources,
+        resolver,
+        Matrix::default(),
+        &mut forms,
+        &mut marked,
+        &mut inline_index,
+        &mut images,
+    )?;
+    Ok(images)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn walk_image_ops(
+    ops: &[Op],
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+    initial_ctm: Matrix,
+    forms: &mut FormStack,
+    marked: &mut MarkedContentStack,
+    inline_index: &mut usize,
+    images: &mut Vec<PositionedImage>,
+) -> Result<(), PdfError> {
+    let mut ctm = initial_ctm;
+    let mut stack: Vec<Matrix> = Vec::new();
     for op in ops {
         match op {
             Op::Save => stack.push(ctm),
-            Op::Restore => ctm = stack.pop().unwrap_or_default(),
+            Op::Restore => ctm = stack.pop().unwrap_or(initial_ctm),
             Op::Transform { matrix } => ctm = concat_matrix(&ctm, matrix),
+            Op::BeginMarkedContent { properties, .. } => marked.begin(
+                properties.as_ref(),
+                resources,
+                resolver,
+                images.len(),
+                forms.in_form(),
+            ),
+            Op::EndMarkedContent => {
+                marked.end();
+            }
             Op::XObject { name } => {
-                if let Some(res) = resources {
-                    if let Some(xobject_ref) = res.xobjects.get(name) {
-                        let xobject = resolver.get(*xobject_ref)?;
-                        if let XObject::Image(image) = &*xobject {
+                if let Some((key, xobject)) = lookup_xobject(name, resources, resolver) {
+                    match &*xobject {
+                        XObject::Image(image) => {
                             let image_data = extract_image(image, resolver)?;
                             let (x0, y0, x1, y1) = unit_square_bounds(&ctm);
                             images.push(PositionedImage {
@@ -847,14 +3002,33 @@ fn collect_positioned_images(
                                 x1,
                                 y1,
                                 image: image_data,
+                                mcid: marked.mcid(),
                             });
                         }
+                        XObject::Form(form) => {
+                            if forms.enter(key) {
+                                if let Some(content) = form_content(form, resources, resolver) {
+                                    walk_image_ops(
+                                        &content.operations,
+                                        content.resources.as_ref(),
+                                        resolver,
+                                        concat_matrix(&ctm, &content.matrix),
+                                        forms,
+                                        marked,
+                                        inline_index,
+                                        images,
+                                    )?;
+                                }
+                                forms.leave();
+                            }
+                        }
+                        _ => {}
                     }
                 }
             }
             Op::InlineImage { image } => {
                 let image_data = extract_image(image, resolver)?;
-                inline_index += 1;
+                *inline_index += 1;
                 let (x0, y0, x1, y1) = unit_square_bounds(&ctm);
                 images.push(PositionedImage {
                     name: format!("inline_{}", inline_index),
@@ -863,12 +3037,13 @@ fn collect_positioned_images(
                     x1,
                     y1,
                     image: image_data,
+                    mcid: marked.mcid(),
                 });
             }
             _ => {}
         }
     }
-    Ok(images)
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -993,7 +3168,29 @@ fn assign_captions_to_objects(
                 text: caption.combined.clone(),
                 bbox: caption.bbox,
             });
-            assigned[caption_idx] = true;
+            assigned[caption_idx] = true;
+        }
+    }
+}
+
+/// Whether a caption introduces a table (`表…` or `Table …`) rather than a figure.
+fn is_table_caption(layout: &FinalTextLayout) -> bool {
+    let first_line = layout.lines.first().map(|line| line.as_str()).unwrap_or("");
+    let trimmed =
+        first_line.trim_start_matches(|c: char| c.is_whitespace() || c == '(' || c == '[');
+    layout.is_caption && (trimmed.starts_with('表') || trimmed.to_lowercase().starts_with("table"))
+}
+
+fn assign_captions_to_tables(tables: &mut [Table], text_layouts: &[FinalTextLayout]) {
+    for caption in text_layouts
+        .iter()
+        .filter(|layout| is_table_caption(layout))
+    {
+        if let Some(best_idx) = best_caption_index(tables, caption.bbox, |table| table.bbox) {
+            tables[best_idx].captions.push(CaptionInfo {
+                text: caption.combined.clone(),
+                bbox: caption.bbox,
+            });
         }
     }
 }
@@ -1080,6 +3277,7 @@ fn extract_image(image: &ImageXObject, resolver: &impl Resolve) -> Result<ImageR
 }
 
 fn text_block_to_pydict(py: Python<'_>, block: TextBlock) -> PyResult<Py<PyDict>> {
+    let invisible = block.is_invisible();
     let TextBlock {
         text,
         x0,
@@ -1088,6 +3286,14 @@ fn text_block_to_pydict(py: Python<'_>, block: TextBlock) -> PyResult<Py<PyDict>
         y1,
         baseline_x,
         baseline_y,
+        font_name,
+        font_size,
+        font_flags,
+        fill_color,
+        render_mode,
+        glyphs: _,
+        mcid,
+        show_index: _,
     } = block;
     let dict = PyDict::new(py);
     dict.set_item("type", "text")?;
@@ -1098,6 +3304,16 @@ fn text_block_to_pydict(py: Python<'_>, block: TextBlock) -> PyResult<Py<PyDict>
     dict.set_item("y0", y0 as f64)?;
     dict.set_item("x1", x1 as f64)?;
     dict.set_item("y1", y1 as f64)?;
+    dict.set_item("font", font_name)?;
+    dict.set_item("size", font_size as f64)?;
+    dict.set_item("bold", font_flags.bold)?;
+    dict.set_item("italic", font_flags.italic)?;
+    dict.set_item("serif", font_flags.serif)?;
+    dict.set_item("fixed_pitch", font_flags.fixed_pitch)?;
+    dict.set_item("color", fill_color.map(color_to_tuple))?;
+    dict.set_item("render_mode", render_mode)?;
+    dict.set_item("invisible", invisible)?;
+    dict.set_item("mcid", mcid)?;
     Ok(dict.into())
 }
 
@@ -1108,6 +3324,46 @@ fn text_blocks_to_pydicts(py: Python<'_>, blocks: Vec<TextBlock>) -> PyResult<Ve
         .collect()
 }
 
+fn text_glyph_to_pydict(
+    py: Python<'_>,
+    glyph: TextGlyph,
+    block_index: usize,
+) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("type", "char")?;
+    dict.set_item("text", glyph.text)?;
+    dict.set_item("code", glyph.code)?;
+    dict.set_item("x", glyph.origin_x as f64)?;
+    dict.set_item("y", glyph.origin_y as f64)?;
+    set_bbox(&dict, (glyph.x0, glyph.y0, glyph.x1, glyph.y1))?;
+    dict.set_item("size", glyph.font_size as f64)?;
+    dict.set_item("block_index", block_index)?;
+    Ok(dict.into())
+}
+
+fn text_glyphs_to_pydicts(py: Python<'_>, blocks: Vec<TextBlock>) -> PyResult<Vec<Py<PyDict>>> {
+    let mut dicts = Vec::new();
+    for (block_index, block) in blocks.into_iter().enumerate() {
+        for glyph in block.glyphs {
+            dicts.push(text_glyph_to_pydict(py, glyph, block_index)?);
+        }
+    }
+    Ok(dicts)
+}
+
+fn text_word_to_pydict(py: Python<'_>, word: TextWord) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("type", "word")?;
+    dict.set_item("text", word.text)?;
+    dict.set_item("x", word.origin_x as f64)?;
+    dict.set_item("y", word.origin_y as f64)?;
+    set_bbox(&dict, word.bbox)?;
+    dict.set_item("font", word.font_name)?;
+    dict.set_item("size", word.font_size as f64)?;
+    dict.set_item("block_index", word.block_index)?;
+    Ok(dict.into())
+}
+
 fn positioned_image_to_pydict(py: Python<'_>, positioned: PositionedImage) -> PyResult<Py<PyDict>> {
     let PositionedImage {
         name,
@@ -1116,6 +3372,7 @@ fn positioned_image_to_pydict(py: Python<'_>, positioned: PositionedImage) -> Py
         x1,
         y1,
         image,
+        mcid,
     } = positioned;
     let ImageResult {
         data,
@@ -1136,6 +3393,7 @@ fn positioned_image_to_pydict(py: Python<'_>, positioned: PositionedImage) -> Py
     dict.set_item("height", height)?;
     dict.set_item("format", format)?;
     dict.set_item("data", PyBytes::new(py, &data))?;
+    dict.set_item("mcid", mcid)?;
     Ok(dict.into())
 }
 
@@ -1232,6 +3490,7 @@ fn path_segment_to_pydict(
     py: Python<'_>,
     kind: String,
     points: Vec<(f32, f32)>,
+    style: &PathStyle,
 ) -> PyResult<Py<PyDict>> {
     let dict = PyDict::new(py);
     dict.set_item("type", "path")?;
@@ -1244,6 +3503,54 @@ fn path_segment_to_pydict(
         dict.set_item("x1", x1 as f64)?;
         dict.set_item("y1", y1 as f64)?;
     }
+    set_path_style(&dict, style)?;
+    Ok(dict.into())
+}
+
+fn set_path_style(dict: &Bound<PyDict>, style: &PathStyle) -> PyResult<()> {
+    dict.set_item("stroke_color", style.stroke_color.map(color_to_tuple))?;
+    dict.set_item("fill_color", style.fill_color.map(color_to_tuple))?;
+    dict.set_item("line_width", style.line_width as f64)?;
+    dict.set_item("effective_line_width", style.effective_line_width() as f64)?;
+    dict.set_item("line_cap", style.line_cap)?;
+    dict.set_item("line_join", style.line_join)?;
+    let dash_array: Vec<f64> = style.dash_array.iter().map(|&value| value as f64).collect();
+    dict.set_item("dash_array", dash_array)?;
+    dict.set_item("dash_phase", style.dash_phase as f64)?;
+    dict.set_item("stroke_opacity", style.stroke_alpha as f64)?;
+    dict.set_item("fill_opacity", style.fill_alpha as f64)?;
+    dict.set_item("fill_rule", style.fill_rule)?;
+    dict.set_item("paint", style.paint.as_str())?;
+    dict.set_item("clip", style.clip)?;
+    Ok(())
+}
+
+fn path_shape_to_pydict(py: Python<'_>, shape: &PathShape) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("type", "shape")?;
+    let mut subpaths = Vec::with_capacity(shape.subpaths.len());
+    for subpath in &shape.subpaths {
+        let mut commands = Vec::with_capacity(subpath.len());
+        for command in subpath {
+            let entry = PyDict::new(py);
+            entry.set_item("command", command.name())?;
+            let points: Vec<(f64, f64)> = command
+                .points()
+                .iter()
+                .map(|&(x, y)| (x as f64, y as f64))
+                .collect();
+            entry.set_item("points", points)?;
+            commands.push(entry);
+        }
+        subpaths.push(PyList::new(py, commands)?);
+    }
+    dict.set_item("subpaths", subpaths)?;
+    dict.set_item("d", shape.svg_path_data())?;
+    if let Some(bbox) = shape.bbox() {
+        set_bbox(&dict, bbox)?;
+    }
+    set_path_style(&dict, &shape.style)?;
+    dict.set_item("mcid", shape.mcid)?;
     Ok(dict.into())
 }
 
@@ -1319,11 +3626,162 @@ fn text_layouts_to_pydicts(
         let lines_list = PyList::new(py, &layout.lines)?;
         dict.set_item("lines", lines_list)?;
         dict.set_item("is_caption", layout.is_caption)?;
+        dict.set_item("reading_order", layout.reading_order)?;
+        dict.set_item("role", layout.role.as_str())?;
+        dict.set_item("heading_level", layout.role.heading_level())?;
+        dict.set_item("font_size", layout.font_size as f64)?;
         dicts.push(dict.into());
     }
     Ok(dicts)
 }
 
+fn table_cell_to_pydict(py: Python<'_>, cell: &TableCell) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("row", cell.row)?;
+    dict.set_item("column", cell.column)?;
+    dict.set_item("row_span", cell.row_span)?;
+    dict.set_item("column_span", cell.column_span)?;
+    set_bbox(&dict, cell.bbox)?;
+    dict.set_item("text", cell.text.as_str())?;
+    Ok(dict.into())
+}
+
+fn table_to_pydict(py: Python<'_>, table: &Table) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("type", "table")?;
+    dict.set_item("method", table.method.as_str())?;
+    dict.set_item("x", table.bbox.0 as f64)?;
+    dict.set_item("y", table.bbox.1 as f64)?;
+    set_bbox(&dict, table.bbox)?;
+    dict.set_item("width", (table.bbox.2 - table.bbox.0) as f64)?;
+    dict.set_item("height", (table.bbox.3 - table.bbox.1) as f64)?;
+    dict.set_item("row_count", table.row_count)?;
+    dict.set_item("column_count", table.column_count)?;
+    let cell_dicts = table
+        .cells
+        .iter()
+        .map(|cell| table_cell_to_pydict(py, cell))
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("cells", PyList::new(py, &cell_dicts)?)?;
+    dict.set_item("rows", table.grid())?;
+    let caption_dicts = captions_to_pydicts(py, &table.captions)?;
+    dict.set_item("captions", PyList::new(py, &caption_dicts)?)?;
+    Ok(dict.into())
+}
+
+fn search_hit_to_pydict(
+    py: Python<'_>,
+    page_index: usize,
+    hit: search::SearchHit,
+) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("type", "search_hit")?;
+    dict.set_item("page_index", page_index)?;
+    dict.set_item("text", hit.text)?;
+    if let Some(bbox) = hit.rects.iter().copied().reduce(bbox_union) {
+        set_bbox(&dict, bbox)?;
+    }
+    let mut rects = Vec::with_capacity(hit.rects.len());
+    for rect in hit.rects {
+        let rect_dict = PyDict::new(py);
+        set_bbox(&rect_dict, rect)?;
+        rects.push(rect_dict);
+    }
+    dict.set_item("rects", PyList::new(py, rects)?)?;
+    Ok(dict.into())
+}
+
+/// Structure types laid out inline, whose text is joined to their siblings without a break.
+fn is_inline_role(role: &str) -> bool {
+    matches!(
+        role,
+        "Span" | "Quote" | "Note" | "Reference" | "BibEntry" | "Code" | "Link" | "Annot" | "Lbl"
+    )
+}
+
+/// Convert a structure element and its kids, also returning the element's text and its page
+/// and bbox so the parent can aggregate them.
+fn struct_element_to_pydict(
+    py: Python<'_>,
+    element: &StructElement,
+    content: &HashMap<usize, HashMap<u32, MarkedContentItem>>,
+) -> PyResult<(Py<PyDict>, String, Option<(usize, BBox)>)> {
+    let mut children = Vec::with_capacity(element.kids.len());
+    let mut text = String::new();
+    let mut area: Option<(usize, BBox)> = None;
+    let mut add_area = |page_index: usize, bbox: BBox| match &mut area {
+        Some((page, current)) if *page == page_index => *current = bbox_union(*current, bbox),
+        Some(_) => {}
+        None => area = Some((page_index, bbox)),
+    };
+    for kid in &element.kids {
+        let dict = match kid {
+            StructKid::Element(child) => {
+                let (child_dict, child_text, child_area) =
+                    struct_element_to_pydict(py, child, content)?;
+                if !text.is_empty() && !child_text.is_empty() && !is_inline_role(&child.role) {
+                    text.push('\n');
+                }
+                text.push_str(&child_text);
+                if let Some((page_index, bbox)) = child_area {
+                    add_area(page_index, bbox);
+                }
+                children.push(child_dict);
+                continue;
+            }
+            StructKid::MarkedContent { page_index, mcid } => {
+                let dict = PyDict::new(py);
+                let item = page_index.and_then(|page_index| content.get(&page_index)?.get(mcid));
+                dict.set_item("type", "marked_content")?;
+                dict.set_item("page_index", *page_index)?;
+                dict.set_item("mcid", *mcid)?;
+                if let Some(item) = item {
+                    dict.set_item("kinds", item.kinds.clone())?;
+                    dict.set_item("text", item.text.as_str())?;
+                    text.push_str(&item.text);
+                    if let (Some(page_index), Some(bbox)) = (page_index, item.bbox) {
+                        set_bbox(&dict, bbox)?;
+                        add_area(*page_index, bbox);
+                    }
+                }
+                dict
+            }
+            StructKid::Object { page_index } => {
+                let dict = PyDict::new(py);
+                dict.set_item("type", "object_reference")?;
+                dict.set_item("page_index", *page_index)?;
+                dict
+            }
+        };
+        children.push(dict.into());
+    }
+    let text = match (&element.actual_text, &element.alt) {
+        (Some(actual_text), _) => actual_text.clone(),
+        (None, Some(alt)) if text.trim().is_empty() => alt.clone(),
+        _ => text,
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("type", "struct_element")?;
+    dict.set_item("role", element.role.as_str())?;
+    dict.set_item("raw_role", element.raw_role.as_str())?;
+    dict.set_item("title", element.title.as_deref())?;
+    dict.set_item("lang", element.lang.as_deref())?;
+    dict.set_item("alt", element.alt.as_deref())?;
+    dict.set_item("actual_text", element.actual_text.as_deref())?;
+    dict.set_item(
+        "page_index",
+        element
+            .page_index
+            .or(area.map(|(page_index, _)| page_index)),
+    )?;
+    dict.set_item("text", text.as_str())?;
+    if let Some((_, bbox)) = area {
+        set_bbox(&dict, bbox)?;
+    }
+    dict.set_item("children", PyList::new(py, children)?)?;
+    Ok((dict.into(), text, area))
+}
+
 fn image_layouts_to_pydicts(
     py: Python<'_>,
     layouts: &[ImageLayout],
@@ -1387,38 +3845,213 @@ fn get_page<'a>(
 }
 
 #[pyfunction]
+#[pyo3(signature = (
+    path,
+    page_index,
+    *,
+    invisible_text = "include",
+    unicode_form = None,
+    expand_ligatures = false,
+    dehyphenate = false,
+    collapse_whitespace = false,
+    coords = "pdf",
+    dpi = 144.0
+))]
+#[allow(clippy::too_many_arguments)]
 fn extract_text_with_coords(
     py: Python<'_>,
     path: &str,
     page_index: usize,
+    invisible_text: &str,
+    unicode_form: Option<&str>,
+    expand_ligatures: bool,
+    dehyphenate: bool,
+    collapse_whitespace: bool,
+    coords: &str,
+    dpi: f32,
 ) -> PyResult<Vec<Py<PyDict>>> {
+    let invisible_filter = InvisibleTextFilter::parse(invisible_text)?;
+    let normalizer = TextNormalizer::new(
+        unicode_form,
+        expand_ligatures,
+        dehyphenate,
+        collapse_whitespace,
+    )?;
     let pdf = open_pdf(path).map_err(pdf_err)?;
     let page = get_page(&pdf, page_index).map_err(pdf_err)?;
     let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
     let resolver = pdf.resolver();
-    let fonts = collect_fonts(page_ref, &resolver).map_err(pdf_err)?;
+    let resources = page_ref.resources().ok();
     let content = match &page_ref.contents {
         Some(content) => content,
         None => return Ok(vec![]),
     };
     let operations = content.operations(&resolver).map_err(pdf_err)?;
-    let blocks = collect_text_blocks(&operations, &fonts);
+    let mut blocks = collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    invisible_filter.apply(&mut blocks);
+    normalizer.normalize_blocks(&mut blocks);
+    output.map_blocks(&mut blocks);
     text_blocks_to_pydicts(py, blocks)
 }
 
 #[pyfunction]
-fn extract_images(py: Python<'_>, path: &str, page_index: usize) -> PyResult<Vec<Py<PyDict>>> {
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_chars(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let pdf = open_pdf(path).map_err(pdf_err)?;
+    let page = get_page(&pdf, page_index).map_err(pdf_err)?;
+    let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
+    let resolver = pdf.resolver();
+    let resources = page_ref.resources().ok();
+    let content = match &page_ref.contents {
+        Some(content) => content,
+        None => return Ok(vec![]),
+    };
+    let operations = content.operations(&resolver).map_err(pdf_err)?;
+    let mut blocks = collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    output.map_blocks(&mut blocks);
+    text_glyphs_to_pydicts(py, blocks)
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_words(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
     let pdf = open_pdf(path).map_err(pdf_err)?;
     let page = get_page(&pdf, page_index).map_err(pdf_err)?;
     let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
     let resolver = pdf.resolver();
+    let resources = page_ref.resources().ok();
     let content = match &page_ref.contents {
         Some(content) => content,
         None => return Ok(vec![]),
     };
     let operations = content.operations(&resolver).map_err(pdf_err)?;
+    let blocks = collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    let mut words = build_words(&blocks);
+    output.map_words(&mut words);
+    words
+        .into_iter()
+        .map(|word| text_word_to_pydict(py, word))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_tables(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let pdf = open_pdf(path).map_err(pdf_err)?;
+    let page = get_page(&pdf, page_index).map_err(pdf_err)?;
+    let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
+    let resolver = pdf.resolver();
     let resources = page_ref.resources().ok();
-    let images = collect_positioned_images(&operations, resources, &resolver).map_err(pdf_err)?;
+    let content = match &page_ref.contents {
+        Some(content) => content,
+        None => return Ok(vec![]),
+    };
+    let operations = content.operations(&resolver).map_err(pdf_err)?;
+    let blocks = collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    let words = build_words(&blocks);
+    let segments = collect_paths(&operations, resources, &resolver).map_err(pdf_err)?;
+    let mut tables = tables::detect_tables(&segments, &words);
+    assign_captions_to_tables(&mut tables, &build_text_layouts(&blocks));
+    output.map_tables(&mut tables);
+    tables
+        .iter()
+        .map(|table| table_to_pydict(py, table))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    path,
+    query,
+    *,
+    case_sensitive = false,
+    regex = false,
+    pages = None,
+    coords = "pdf",
+    dpi = 144.0
+))]
+#[allow(clippy::too_many_arguments)]
+fn search_text(
+    py: Python<'_>,
+    path: &str,
+    query: &str,
+    case_sensitive: bool,
+    regex: bool,
+    pages: Option<Vec<usize>>,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let pattern = search::compile_query(query, case_sensitive, regex)
+        .map_err(|err| PyRuntimeError::new_err(format!("invalid search query: {err}")))?;
+    let pdf = open_pdf(path).map_err(pdf_err)?;
+    let resolver = pdf.resolver();
+    let page_indices = pages.unwrap_or_else(|| (0..pdf.num_pages() as usize).collect());
+    let mut hits = Vec::new();
+    for page_index in page_indices {
+        let page = get_page(&pdf, page_index).map_err(pdf_err)?;
+        let page_ref: &PdfPage = &page;
+        let output = OutputCoords::new(coords, dpi, page_ref)?;
+        let resources = page_ref.resources().ok();
+        let content = match &page_ref.contents {
+            Some(content) => content,
+            None => continue,
+        };
+        let operations = content.operations(&resolver).map_err(pdf_err)?;
+        let blocks = collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+        let mut page_hits = search::search_blocks(&blocks, &pattern);
+        output.map_search_hits(&mut page_hits);
+        for hit in page_hits {
+            hits.push(search_hit_to_pydict(py, page_index, hit)?);
+        }
+    }
+    Ok(hits)
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_images(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let pdf = open_pdf(path).map_err(pdf_err)?;
+    let page = get_page(&pdf, page_index).map_err(pdf_err)?;
+    let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
+    let resolver = pdf.resolver();
+    let content = match &page_ref.contents {
+        Some(content) => content,
+        None => return Ok(vec![]),
+    };
+    let operations = content.operations(&resolver).map_err(pdf_err)?;
+    let resources = page_ref.resources().ok();
+    let mut images =
+        collect_positioned_images(&operations, resources, &resolver).map_err(pdf_err)?;
+    output.map_images(&mut images);
     positioned_images_to_pydicts(py, images)
 }
 
@@ -1578,21 +4211,83 @@ fn extract_region_images(
 }
 
 #[pyfunction]
-fn extract_paths(path: &str, page_index: usize) -> PyResult<Vec<(String, Vec<(f32, f32)>)>> {
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_paths(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let pdf = open_pdf(path).map_err(pdf_err)?;
+    let page = get_page(&pdf, page_index).map_err(pdf_err)?;
+    let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
+    let resolver = pdf.resolver();
+    let content = match &page_ref.contents {
+        Some(content) => content,
+        None => return Ok(vec![]),
+    };
+    let operations = content.operations(&resolver).map_err(pdf_err)?;
+    let resources = page_ref.resources().ok();
+    let mut paths = collect_marked_paths(&operations, resources, &resolver).map_err(pdf_err)?;
+    output.map_segments(&mut paths.segments);
+    output.map_path_styles(&mut paths.styles);
+    paths
+        .segments
+        .into_iter()
+        .zip(&paths.styles)
+        .map(|((kind, points), style)| path_segment_to_pydict(py, kind, points, style))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_shapes(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
     let pdf = open_pdf(path).map_err(pdf_err)?;
     let page = get_page(&pdf, page_index).map_err(pdf_err)?;
     let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
     let resolver = pdf.resolver();
     let content = match &page_ref.contents {
         Some(content) => content,
         None => return Ok(vec![]),
     };
     let operations = content.operations(&resolver).map_err(pdf_err)?;
-    Ok(collect_paths(&operations))
+    let resources = page_ref.resources().ok();
+    let mut paths = collect_marked_paths(&operations, resources, &resolver).map_err(pdf_err)?;
+    output.map_shapes(&mut paths.shapes);
+    paths
+        .shapes
+        .iter()
+        .map(|shape| path_shape_to_pydict(py, shape))
+        .collect()
 }
 
 #[pyfunction]
-#[pyo3(signature = (path, page_index, text_color = None, image_color = None, object_color = None))]
+#[pyo3(signature = (
+    path,
+    page_index,
+    text_color = None,
+    image_color = None,
+    object_color = None,
+    *,
+    invisible_text = "include",
+    unicode_form = None,
+    expand_ligatures = false,
+    dehyphenate = false,
+    collapse_whitespace = false,
+    coords = "pdf",
+    dpi = 144.0,
+    include_unpainted_paths = false
+))]
+#[allow(clippy::too_many_arguments)]
 fn extract_layouts(
     py: Python<'_>,
     path: &str,
@@ -1600,20 +4295,38 @@ fn extract_layouts(
     text_color: Option<(f32, f32, f32)>,
     image_color: Option<(f32, f32, f32)>,
     object_color: Option<(f32, f32, f32)>,
+    invisible_text: &str,
+    unicode_form: Option<&str>,
+    expand_ligatures: bool,
+    dehyphenate: bool,
+    collapse_whitespace: bool,
+    coords: &str,
+    dpi: f32,
+    include_unpainted_paths: bool,
 ) -> PyResult<Vec<Py<PyDict>>> {
+    let invisible_filter = InvisibleTextFilter::parse(invisible_text)?;
+    let normalizer = TextNormalizer::new(
+        unicode_form,
+        expand_ligatures,
+        dehyphenate,
+        collapse_whitespace,
+    )?;
     let pdf = open_pdf(path).map_err(pdf_err)?;
     let page = get_page(&pdf, page_index).map_err(pdf_err)?;
     let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
     let resolver = pdf.resolver();
-    let fonts = collect_fonts(page_ref, &resolver).map_err(pdf_err)?;
     let resources = page_ref.resources().ok();
     let content = match &page_ref.contents {
         Some(content) => content,
         None => return Ok(vec![]),
     };
     let operations = content.operations(&resolver).map_err(pdf_err)?;
-    let text_blocks = collect_text_blocks(&operations, &fonts);
-    let text_layouts = build_text_layouts(&text_blocks);
+    let mut text_blocks =
+        collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    invisible_filter.apply(&mut text_blocks);
+    let mut text_layouts = build_text_layouts(&text_blocks);
+    normalizer.normalize_layouts(&mut text_layouts, &text_blocks);
     let caption_indices: Vec<usize> = text_layouts
         .iter()
         .enumerate()
@@ -1622,7 +4335,12 @@ fn extract_layouts(
     let mut caption_assigned = vec![false; text_layouts.len()];
     let images = collect_positioned_images(&operations, resources, &resolver).map_err(pdf_err)?;
     let mut image_layouts = build_image_layouts(&images);
-    let path_segments = collect_paths(&operations);
+    let paths = collect_marked_paths(&operations, resources, &resolver).map_err(pdf_err)?;
+    let path_segments = if include_unpainted_paths {
+        paths.segments
+    } else {
+        paths.painted_segments()
+    };
     let mut object_layouts = build_object_layouts(&path_segments);
     assign_captions_to_images(
         &mut image_layouts,
@@ -1636,6 +4354,9 @@ fn extract_layouts(
         &caption_indices,
         &mut caption_assigned,
     );
+    output.map_text_layouts(&mut text_layouts);
+    output.map_image_layouts(&mut image_layouts);
+    output.map_object_layouts(&mut object_layouts);
     let colors = LayoutColors::new(text_color, image_color, object_color);
 
     let mut layouts = text_layouts_to_pydicts(py, &text_layouts, colors.text)?;
@@ -1649,12 +4370,19 @@ fn extract_layouts(
 }
 
 #[pyfunction]
-fn extract_page_content(py: Python<'_>, path: &str, page_index: usize) -> PyResult<Py<PyDict>> {
+#[pyo3(signature = (path, page_index, *, coords = "pdf", dpi = 144.0))]
+fn extract_page_content(
+    py: Python<'_>,
+    path: &str,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Py<PyDict>> {
     let pdf = open_pdf(path).map_err(pdf_err)?;
     let page = get_page(&pdf, page_index).map_err(pdf_err)?;
     let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
     let resolver = pdf.resolver();
-    let fonts = collect_fonts(page_ref, &resolver).map_err(pdf_err)?;
     let resources = page_ref.resources().ok();
     let content = match &page_ref.contents {
         Some(content) => content,
@@ -1670,18 +4398,20 @@ fn extract_page_content(py: Python<'_>, path: &str, page_index: usize) -> PyResu
         }
     };
     let operations = content.operations(&resolver).map_err(pdf_err)?;
-    let text_blocks = collect_text_blocks(&operations, &fonts);
-    let text_layouts = build_text_layouts(&text_blocks);
+    let mut text_blocks =
+        collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    let mut text_layouts = build_text_layouts(&text_blocks);
     let caption_indices: Vec<usize> = text_layouts
         .iter()
         .enumerate()
         .filter_map(|(idx, layout)| if layout.is_caption { Some(idx) } else { None })
         .collect();
     let mut caption_assigned = vec![false; text_layouts.len()];
-    let images = collect_positioned_images(&operations, resources, &resolver).map_err(pdf_err)?;
+    let mut images =
+        collect_positioned_images(&operations, resources, &resolver).map_err(pdf_err)?;
     let mut image_layouts = build_image_layouts(&images);
-    let path_segments = collect_paths(&operations);
-    let mut object_layouts = build_object_layouts(&path_segments);
+    let mut paths = collect_marked_paths(&operations, resources, &resolver).map_err(pdf_err)?;
+    let mut object_layouts = build_object_layouts(&paths.painted_segments());
     assign_captions_to_images(
         &mut image_layouts,
         &text_layouts,
@@ -1694,12 +4424,19 @@ fn extract_page_content(py: Python<'_>, path: &str, page_index: usize) -> PyResu
         &caption_indices,
         &mut caption_assigned,
     );
+    output.map_blocks(&mut text_blocks);
+    output.map_images(&mut images);
+    output.map_segments(&mut paths.segments);
+    output.map_path_styles(&mut paths.styles);
+    output.map_text_layouts(&mut text_layouts);
+    output.map_image_layouts(&mut image_layouts);
+    output.map_object_layouts(&mut object_layouts);
 
     let text_entries = text_blocks_to_pydicts(py, text_blocks.clone())?;
     let image_entries = positioned_images_to_pydicts(py, images)?;
-    let mut object_entries = Vec::with_capacity(path_segments.len());
-    for (kind, points) in &path_segments {
-        object_entries.push(path_segment_to_pydict(py, kind.clone(), points.clone())?);
+    let mut object_entries = Vec::with_capacity(paths.segments.len());
+    for ((kind, points), style) in paths.segments.into_iter().zip(&paths.styles) {
+        object_entries.push(path_segment_to_pydict(py, kind, points, style)?);
     }
 
     let colors = LayoutColors::new(None, None, None);
@@ -1738,6 +4475,108 @@ fn extract_page_content(py: Python<'_>, path: &str, page_index: usize) -> PyResu
     Ok(page_dict.into())
 }
 
+/// Text, images and paths of a page grouped by the MCID they were drawn under.
+fn page_marked_content(
+    pdf: &CachedFile<Vec<u8>>,
+    page_index: usize,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<HashMap<u32, MarkedContentItem>> {
+    let page = get_page(pdf, page_index).map_err(pdf_err)?;
+    let page_ref: &PdfPage = &page;
+    let output = OutputCoords::new(coords, dpi, page_ref)?;
+    let resolver = pdf.resolver();
+    let resources = page_ref.resources().ok();
+    let content = match &page_ref.contents {
+        Some(content) => content,
+        None => return Ok(HashMap::new()),
+    };
+    let operations = content.operations(&resolver).map_err(pdf_err)?;
+    let blocks = collect_text_blocks(&operations, resources, &resolver).map_err(pdf_err)?;
+    let images = collect_positioned_images(&operations, resources, &resolver).map_err(pdf_err)?;
+    let paths = collect_marked_paths(&operations, resources, &resolver).map_err(pdf_err)?;
+    let (segments, mcids): (Vec<_>, Vec<_>) = paths
+        .segments
+        .into_iter()
+        .zip(paths.mcids)
+        .zip(&paths.styles)
+        .filter(|(_, style)| style.paint.is_painted())
+        .map(|(path, _)| path)
+        .unzip();
+    let mut items = structure::marked_content_items(&blocks, &images, &segments, &mcids);
+    output.map_marked_content(&mut items);
+    Ok(items)
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, *, coords = "pdf", dpi = 144.0))]
+fn extract_structure(
+    py: Python<'_>,
+    path: &str,
+    coords: &str,
+    dpi: f32,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let pdf = open_pdf(path).map_err(pdf_err)?;
+    let resolver = pdf.resolver();
+    let catalog = resolver
+        .resolve(pdf.trailer.root.get_ref().get_inner())
+        .and_then(Primitive::into_dictionary)
+        .map_err(pdf_err)?;
+    let page_refs = structure::page_references(&catalog, &resolver).map_err(pdf_err)?;
+    let elements =
+        structure::read_structure_tree(&catalog, &page_refs, &resolver).map_err(pdf_err)?;
+    let mut pages = HashSet::new();
+    for element in &elements {
+        element.pages(&mut pages);
+    }
+    let mut content = HashMap::new();
+    for page_index in pages {
+        content.insert(
+            page_index,
+            page_marked_content(&pdf, page_index, coords, dpi)?,
+        );
+    }
+    elements
+        .iter()
+        .map(|element| struct_element_to_pydict(py, element, &content).map(|(dict, ..)| dict))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, pages = None, *, image_dir = None, image_link_prefix = None))]
+fn export_markdown(
+    path: &str,
+    pages: Option<Vec<usize>>,
+    image_dir: Option<&str>,
+    image_link_prefix: Option<&str>,
+) -> PyResult<String> {
+    let export = image_dir.map(|dir| markdown::ImageExport {
+        directory: Path::new(dir),
+        link_prefix: image_link_prefix.unwrap_or(dir),
+    });
+    markdown::export_markdown(path, pages.as_deref(), export.as_ref())
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, pages = None, *, dpi = 300.0))]
+fn export_hocr(path: &str, pages: Option<Vec<usize>>, dpi: f32) -> PyResult<String> {
+    ocr_xml::export_ocr_xml(path, pages.as_deref(), dpi, ocr_xml::OcrXmlFormat::Hocr)
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, pages = None, *, dpi = 300.0))]
+fn export_alto(path: &str, pages: Option<Vec<usize>>, dpi: f32) -> PyResult<String> {
+    ocr_xml::export_ocr_xml(path, pages.as_deref(), dpi, ocr_xml::OcrXmlFormat::Alto)
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+#[pyfunction]
+fn page_to_svg(path: &str, page_index: usize) -> PyResult<String> {
+    svg::page_to_svg(path, page_index).map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
 #[pyfunction]
 #[pyo3(signature = (x0, y0, x1, y1, color = None))]
 fn make_rectangle_outline(
@@ -1830,13 +4669,91 @@ fn vectorize_image_bytes(image_bytes: &[u8], max_dimension: u32) -> PyResult<Py<
 fn pdfvectorizer(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(get_page_count, m)?)?;
     m.add_function(wrap_pyfunction!(extract_text_with_coords, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_chars, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_words, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_tables, m)?)?;
+    m.add_function(wrap_pyfunction!(search_text, m)?)?;
     m.add_function(wrap_pyfunction!(extract_images, m)?)?;
     m.add_function(wrap_pyfunction!(extract_region_images, m)?)?;
     m.add_function(wrap_pyfunction!(extract_paths, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_shapes, m)?)?;
     m.add_function(wrap_pyfunction!(extract_layouts, m)?)?;
     m.add_function(wrap_pyfunction!(extract_page_content, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_structure, m)?)?;
+    m.add_function(wrap_pyfunction!(export_markdown, m)?)?;
+    m.add_function(wrap_pyfunction!(export_hocr, m)?)?;
+    m.add_function(wrap_pyfunction!(export_alto, m)?)?;
+    m.add_function(wrap_pyfunction!(page_to_svg, m)?)?;
     m.add_function(wrap_pyfunction!(make_rectangle_outline, m)?)?;
     m.add_function(wrap_pyfunction!(vectorize_image, m)?)?;
     m.add_function(wrap_pyfunction!(vectorize_image_bytes, m)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Matrix {
+        Matrix { a, b, c, d, e, f }
+    }
+
+    fn assert_point(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    fn type3(font_matrix: [f32; 6]) -> Type3Metrics {
+        Type3Metrics {
+            font_matrix,
+            first_char: 0,
+            widths: vec![500.0],
+            font_bbox: Some((0.0, -200.0, 1000.0, 800.0)),
+        }
+    }
+
+    #[test]
+    fn type3_width_uses_the_length_of_the_advance() {
+        let scaled = type3([0.001, 0.0, 0.0, 0.001, 0.0, 0.0]);
+        assert!((scaled.glyph_width(0) - 500.0).abs() < 1e-3);
+        let mirrored = type3([-0.001, 0.0, 0.0, 0.001, 0.0, 0.0]);
+        assert!((mirrored.glyph_width(0) - 500.0).abs() < 1e-3);
+        let rotated = type3([0.0, 0.002, -0.002, 0.0, 0.0, 0.0]);
+        assert!((rotated.glyph_width(0) - 1000.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn type3_metrics_use_the_mapped_bbox() {
+        let (ascent, descent) = type3([0.001, 0.0, 0.0, -0.001, 0.0, 0.0])
+            .metrics()
+            .unwrap();
+        assert!((ascent - 200.0).abs() < 1e-3 && (descent + 800.0).abs() < 1e-3);
+        // A skew adds the x extent of the box to its height.
+        let (ascent, descent) = type3([0.001, 0.0005, 0.0, 0.001, 0.0, 0.0])
+            .metrics()
+            .unwrap();
+        assert!((ascent - 1300.0).abs() < 1e-3 && (descent + 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn concat_applies_later_cm_first() {
+        let rotate = matrix(0.0, 1.0, -1.0, 0.0, 0.0, 0.0);
+        let scale = matrix(2.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let ctm = concat_matrix(&concat_matrix(&Matrix::default(), &rotate), &scale);
+
+        assert_point(apply_matrix(&ctm, (1.0, 0.0)), (0.0, 2.0));
+        assert_point(apply_matrix(&ctm, (0.0, 1.0)), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn concat_translates_in_current_space() {
+        let scale = matrix(2.0, 0.0, 0.0, 3.0, 10.0, 20.0);
+        let translate = matrix(1.0, 0.0, 0.0, 1.0, 5.0, 7.0);
+        let ctm = concat_matrix(&scale, &translate);
+
+        assert_point(apply_matrix(&ctm, (0.0, 0.0)), (20.0, 41.0));
+        assert_point(apply_matrix(&ctm, (1.0, 1.0)), (22.0, 44.0));
+    }
+}
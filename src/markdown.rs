@@ -0,0 +1,299 @@
+//! Markdown export built on the layout pipeline of `extract_layouts`.
+//!
+//! Text layouts become headings, list items and paragraphs according to their role; page
+//! headers and footers are dropped. Images are placed with the text by the same XY-cut used for
+//! reading order, with their captions below them. Image files are written only when an
+//! [`ImageExport`] is given; otherwise the links name the file that would have been written.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use pdf::error::PdfError;
+use thiserror::Error;
+
+use crate::normalize::TextNormalizer;
+use crate::{
+    BBox, FinalTextLayout, ImageLayout, LayoutRole, PdfPage, PositionedImage,
+    assign_captions_to_images, build_image_layouts, build_text_layouts, collect_positioned_images,
+    collect_text_blocks, get_page, open_pdf, reading_order,
+};
+
+#[derive(Debug, Error)]
+pub enum MarkdownError {
+    #[error("PDF error: {0}")]
+    Pdf(#[from] PdfError),
+    #[error("failed to write image '{0}': {1}")]
+    WriteImage(String, io::Error),
+}
+
+/// Where to save the images referenced from the Markdown.
+pub struct ImageExport<'a> {
+    /// Directory the image files are written to; created when missing.
+    pub directory: &'a Path,
+    /// Prefix of image links, usually the directory relative to the Markdown file.
+    pub link_prefix: &'a str,
+}
+
+/// Convert the given pages (all pages when `None`) of a PDF into one Markdown document.
+pub fn export_markdown(
+    path: &str,
+    pages: Option<&[usize]>,
+    images: Option<&ImageExport>,
+) -> Result<String, MarkdownError> {
+    let pdf = open_pdf(path)?;
+    let page_indices: Vec<usize> = match pages {
+        Some(pages) => pages.to_vec(),
+        None => (0..pdf.num_pages() as usize).collect(),
+    };
+    if let Some(images) = images {
+        fs::create_dir_all(images.directory).map_err(|err| {
+            MarkdownError::WriteImage(images.directory.display().to_string(), err)
+        })?;
+    }
+    let mut sections = Vec::with_capacity(page_indices.len());
+    for page_index in page_indices {
+        let page = get_page(&pdf, page_index)?;
+        let page_ref: &PdfPage = &page;
+        let resolver = pdf.resolver();
+        let resources = page_ref.resources().ok();
+        let Some(content) = &page_ref.contents else {
+            continue;
+        };
+        let operations = content.operations(&resolver)?;
+        let blocks = collect_text_blocks(&operations, resources, &resolver)?;
+        let mut text_layouts = build_text_layouts(&blocks);
+        TextNormalizer::new(None, true, true, false)
+            .expect("normalization options are valid")
+            .normalize_layouts(&mut text_layouts, &blocks);
+        let positioned = collect_positioned_images(&operations, resources, &resolver)?;
+        let section = page_markdown(page_index, &text_layouts, positioned, images)?;
+        if !section.is_empty() {
+            sections.push(section);
+        }
+    }
+    let mut markdown = sections.join("\n\n");
+    if !markdown.is_empty() {
+        markdown.push('\n');
+    }
+    Ok(markdown)
+}
+
+fn page_markdown(
+    page_index: usize,
+    text_layouts: &[FinalTextLayout],
+    positioned: Vec<PositionedImage>,
+    images: Option<&ImageExport>,
+) -> Result<String, MarkdownError> {
+    let caption_indices: Vec<usize> = text_layouts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, layout)| layout.is_caption.then_some(index))
+        .collect();
+    let mut caption_assigned = vec![false; text_layouts.len()];
+    let mut image_layouts = build_image_layouts(&positioned);
+    assign_captions_to_images(
+        &mut image_layouts,
+        text_layouts,
+        &caption_indices,
+        &mut caption_assigned,
+    );
+
+    let mut boxes: Vec<BBox> = Vec::new();
+    let mut blocks: Vec<String> = Vec::new();
+    for (index, layout) in text_layouts.iter().enumerate() {
+        if caption_assigned[index] {
+            continue;
+        }
+        if let Some(block) = layout_markdown(layout) {
+            boxes.push(layout.bbox);
+            blocks.push(block);
+        }
+    }
+    for (image_index, (layout, image)) in image_layouts.iter().zip(positioned).enumerate() {
+        let file_name = image_file_name(page_index, image_index, &image);
+        let link = match images {
+            Some(export) => {
+                let target = export.directory.join(&file_name);
+                fs::write(&target, &image.image.data)
+                    .map_err(|err| MarkdownError::WriteImage(target.display().to_string(), err))?;
+                join_link(export.link_prefix, &file_name)
+            }
+            None => file_name,
+        };
+        boxes.push(layout.bbox);
+        blocks.push(image_markdown(layout, &link));
+    }
+    let ordered: Vec<&str> = reading_order(&boxes)
+        .into_iter()
+        .map(|index| blocks[index].as_str())
+        .collect();
+    Ok(ordered.join("\n\n"))
+}
+
+/// Markdown for one text layout, or `None` for page furniture and empty layouts.
+fn layout_markdown(layout: &FinalTextLayout) -> Option<String> {
+    let text = join_lines(&layout.lines);
+    if text.is_empty() {
+        return None;
+    }
+    let block = match layout.role {
+        LayoutRole::Header | LayoutRole::Footer => return None,
+        LayoutRole::Title => format!("# {text}"),
+        LayoutRole::Heading(level) => {
+            format!("{} {text}", "#".repeat((level as usize + 1).min(6)))
+        }
+        LayoutRole::ListItem => list_item(&text),
+        LayoutRole::Caption => format!("*{text}*"),
+        LayoutRole::Paragraph | LayoutRole::Footnote => escape_block_start(&text),
+    };
+    Some(block)
+}
+
+fn image_markdown(layout: &ImageLayout, link: &str) -> String {
+    let caption = layout
+        .captions
+        .iter()
+        .map(|caption| join_lines(&[caption.text.clone()]))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let alt = if caption.is_empty() {
+        layout.name.as_str()
+    } else {
+        caption.as_str()
+    };
+    let alt = alt.replace(['[', ']'], "");
+    let mut block = format!("![{alt}]({link})");
+    if !caption.is_empty() {
+        block.push_str(&format!("\n\n*{caption}*"));
+    }
+    block
+}
+
+/// Join the lines of a layout into one line of Markdown, without spaces between CJK lines.
+fn join_lines(lines: &[String]) -> String {
+    let mut joined = String::new();
+    for line in lines.iter().flat_map(|line| line.split('\n')) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let cjk_break = joined.chars().next_back().is_some_and(is_wide)
+            && line.chars().next().is_some_and(is_wide);
+        if !joined.is_empty() && !cjk_break {
+            joined.push(' ');
+        }
+        joined.push_str(line);
+    }
+    joined
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3000}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// Turn a list item into a Markdown list entry, keeping `1.` style numbers as they are.
+fn list_item(text: &str) -> String {
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && text[digits..].starts_with(". ") {
+        return text.to_owned();
+    }
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if !first.is_alphanumeric() && !matches!(first, '(' | '（') => {
+            format!("- {}", chars.as_str().trim_start())
+        }
+        _ => format!("- {text}"),
+    }
+}
+
+/// Escape characters that would make a paragraph start a heading, quote or list.
+fn escape_block_start(text: &str) -> String {
+    match text.chars().next() {
+        Some('#' | '>' | '-' | '+' | '*') => format!("\\{text}"),
+        _ => text.to_owned(),
+    }
+}
+
+/// File name of an image, numbered by its position on the page: resource names such as `/Im0`
+/// repeat across Form XObjects, and one image may be drawn several times.
+fn image_file_name(page_index: usize, image_index: usize, image: &PositionedImage) -> String {
+    let extension = match image.image.format.as_str() {
+        "png" => "png",
+        "jpeg" => "jpg",
+        "jpx" => "jp2",
+        "jbig2" => "jb2",
+        _ => "bin",
+    };
+    let name: String = image
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!(
+        "page{}-{}-{}.{}",
+        page_index + 1,
+        image_index + 1,
+        name,
+        extension
+    )
+}
+
+fn join_link(prefix: &str, file_name: &str) -> String {
+    if prefix.is_empty() {
+        file_name.to_owned()
+    } else {
+        format!("{}/{}", prefix.trim_end_matches('/'), file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn joins_lines_with_spaces() {
+        assert_eq!(
+            join_lines(&lines(&["first line ", "", "second\nthird"])),
+            "first line second third"
+        );
+    }
+
+    #[test]
+    fn joins_wide_lines_without_spaces() {
+        assert_eq!(
+            join_lines(&lines(&["日本語の", "文章です"])),
+            "日本語の文章です"
+        );
+        assert_eq!(join_lines(&lines(&["日本語", "PDF"])), "日本語 PDF");
+    }
+
+    #[test]
+    fn list_items_replace_bullets() {
+        assert_eq!(list_item("• first"), "- first");
+        assert_eq!(list_item("-second"), "- second");
+        assert_eq!(list_item("plain"), "- plain");
+    }
+
+    #[test]
+    fn list_items_keep_numbers_and_parentheses() {
+        assert_eq!(list_item("12. twelfth"), "12. twelfth");
+        assert_eq!(list_item("12 items"), "- 12 items");
+        assert_eq!(list_item("(a) first"), "- (a) first");
+        assert_eq!(list_item("（1）最初"), "- （1）最初");
+    }
+
+    #[test]
+    fn escapes_block_starts() {
+        assert_eq!(escape_block_start("# not a heading"), "\\# not a heading");
+        assert_eq!(escape_block_start("- not a list"), "\\- not a list");
+        assert_eq!(escape_block_start("text"), "text");
+    }
+}
@@ -0,0 +1,219 @@
+//! Optional clean-up of extracted text: Unicode normalization, ligature expansion,
+//! dehyphenation and whitespace collapsing.
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{FinalTextLayout, TextBlock, same_row};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnicodeForm {
+    Nfc,
+    Nfkc,
+}
+
+/// Normalization steps requested by the caller; every step is off by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextNormalizer {
+    form: Option<UnicodeForm>,
+    expand_ligatures: bool,
+    dehyphenate: bool,
+    collapse_whitespace: bool,
+}
+
+impl TextNormalizer {
+    pub fn new(
+        unicode_form: Option<&str>,
+        expand_ligatures: bool,
+        dehyphenate: bool,
+        collapse_whitespace: bool,
+    ) -> PyResult<Self> {
+        let form = match unicode_form.map(str::to_ascii_uppercase).as_deref() {
+            None => None,
+            Some("NFC") => Some(UnicodeForm::Nfc),
+            Some("NFKC") => Some(UnicodeForm::Nfkc),
+            Some(_) => {
+                return Err(PyRuntimeError::new_err(
+                    "unicode_form must be None, \"NFC\" or \"NFKC\"",
+                ));
+            }
+        };
+        Ok(Self {
+            form,
+            expand_ligatures,
+            dehyphenate,
+            collapse_whitespace,
+        })
+    }
+
+    /// Normalize a single piece of text. Soft hyphens are dropped when dehyphenating.
+    pub fn apply(&self, text: &str) -> String {
+        let mut text: String = match self.form {
+            Some(UnicodeForm::Nfc) => text.nfc().collect(),
+            Some(UnicodeForm::Nfkc) => text.nfkc().collect(),
+            None => text.to_owned(),
+        };
+        if self.expand_ligatures {
+            text = expand_ligatures(&text);
+        }
+        if self.dehyphenate {
+            text.retain(|c| c != '\u{AD}');
+        }
+        if self.collapse_whitespace {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        text
+    }
+
+    /// Normalize the lines of one layout, first joining words hyphenated across line ends.
+    /// `row_breaks[i]` tells whether `lines[i + 1]` starts a new row.
+    pub fn apply_lines(&self, lines: &[String], row_breaks: &[bool]) -> Vec<String> {
+        let mut lines = lines.to_vec();
+        if self.dehyphenate {
+            dehyphenate_lines(&mut lines, row_breaks);
+        }
+        lines.iter().map(|line| self.apply(line)).collect()
+    }
+
+    fn is_identity(&self) -> bool {
+        self.form.is_none()
+            && !self.expand_ligatures
+            && !self.dehyphenate
+            && !self.collapse_whitespace
+    }
+
+    /// Normalize the text of extracted blocks; glyphs keep their original text.
+    pub fn normalize_blocks(&self, blocks: &mut [TextBlock]) {
+        if self.is_identity() {
+            return;
+        }
+        for block in blocks {
+            block.text = self.apply(&block.text);
+        }
+    }
+
+    /// Normalize the lines of text layouts built from `blocks` and rebuild their combined text.
+    pub fn normalize_layouts(&self, layouts: &mut [FinalTextLayout], blocks: &[TextBlock]) {
+        if self.is_identity() {
+            return;
+        }
+        for layout in layouts {
+            let row_breaks = row_breaks(layout, blocks);
+            layout.lines = self.apply_lines(&layout.lines, &row_breaks);
+            layout.combined = layout.lines.join("\n");
+        }
+    }
+}
+
+/// Whether each line of a layout after the first starts a new row. The lines are its text
+/// blocks, so a row split by `TJ` or a font change spans several lines.
+fn row_breaks(layout: &FinalTextLayout, blocks: &[TextBlock]) -> Vec<bool> {
+    layout
+        .block_indices
+        .windows(2)
+        .map(|pair| match (blocks.get(pair[0]), blocks.get(pair[1])) {
+            (Some(previous), Some(next)) => !same_row(previous, next),
+            _ => true,
+        })
+        .collect()
+}
+
+fn expand_ligatures(text: &str) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{FB00}' => expanded.push_str("ff"),
+            '\u{FB01}' => expanded.push_str("fi"),
+            '\u{FB02}' => expanded.push_str("fl"),
+            '\u{FB03}' => expanded.push_str("ffi"),
+            '\u{FB04}' => expanded.push_str("ffl"),
+            '\u{FB05}' | '\u{FB06}' => expanded.push_str("st"),
+            _ => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+/// Move the rest of a word hyphenated at a line end ("docu-" / "ment") up to the first line.
+///
+/// Only a hyphen after a letter followed by a lowercase letter on the next row is treated as
+/// hyphenation; lines on one row (`row_breaks[i]` false) are never joined. When the whole next
+/// line moves up and the joined word ends in a hyphen again ("docu-" / "men-" / "tation"), the
+/// join continues with the line after it. Lines emptied by the move are dropped.
+fn dehyphenate_lines(lines: &mut Vec<String>, row_breaks: &[bool]) {
+    let mut joined = false;
+    for index in 0..lines.len().saturating_sub(1) {
+        let mut next_index = index + 1;
+        while next_index < lines.len() && row_breaks.get(next_index - 1).copied().unwrap_or(true) {
+            let current = lines[index].trim_end();
+            let Some(stem) = current.strip_suffix(['-', '\u{AD}', '\u{2010}']) else {
+                break;
+            };
+            if !stem.chars().next_back().is_some_and(char::is_alphabetic) {
+                break;
+            }
+            let next = lines[next_index].trim_start();
+            if !next.chars().next().is_some_and(char::is_lowercase) {
+                break;
+            }
+            let (head, rest) = next.split_at(next.find(char::is_whitespace).unwrap_or(next.len()));
+            let (merged, remainder) = (format!("{stem}{head}"), rest.trim_start().to_owned());
+            lines[index] = merged;
+            lines[next_index] = remainder;
+            joined = true;
+            if !lines[next_index].is_empty() {
+                break;
+            }
+            next_index += 1;
+        }
+    }
+    if joined {
+        lines.retain(|line| !line.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn joins_hyphenated_word_across_rows() {
+        let mut text = lines(&["a well-", "known fact"]);
+        dehyphenate_lines(&mut text, &[true]);
+        assert_eq!(text, lines(&["a wellknown", "fact"]));
+    }
+
+    #[test]
+    fn keeps_hyphen_between_blocks_on_one_row() {
+        // `(well-) Tj (known) Tj` on one baseline: the hyphen is part of the word.
+        let mut text = lines(&["a well-", "known fact"]);
+        dehyphenate_lines(&mut text, &[false]);
+        assert_eq!(text, lines(&["a well-", "known fact"]));
+    }
+
+    #[test]
+    fn drops_lines_emptied_by_the_join() {
+        let mut text = lines(&["docu-", "ment", "next"]);
+        dehyphenate_lines(&mut text, &[true, true]);
+        assert_eq!(text, lines(&["document", "next"]));
+    }
+
+    #[test]
+    fn joins_chained_hyphenation() {
+        let mut text = lines(&["docu-", "men-", "tation", "next"]);
+        dehyphenate_lines(&mut text, &[true, true, true]);
+        assert_eq!(text, lines(&["documentation", "next"]));
+    }
+
+    #[test]
+    fn chained_join_stops_at_a_line_on_the_same_row() {
+        let mut text = lines(&["docu-", "men-", "tation"]);
+        dehyphenate_lines(&mut text, &[true, false]);
+        assert_eq!(text, lines(&["documen-", "tation"]));
+    }
+}
@@ -0,0 +1,448 @@
+//! hOCR and ALTO XML export of the text layout.
+//!
+//! Both formats describe a page as blocks of lines of words with pixel bounding boxes, as if
+//! the page had been scanned at the requested DPI. Blocks are the layouts of
+//! `build_text_layouts` in reading order; their text blocks that share a row form a line, and
+//! the words of `build_words` are attached to the line of the block holding their first glyph.
+//! Coordinates go through [`PageGeometry`], so they match `coords="pixels"` and pages rendered
+//! at the same DPI.
+
+use pdf::error::PdfError;
+use pdf::file::CachedFile;
+use thiserror::Error;
+
+use crate::{
+    BBox, PageGeometry, PdfPage, TextBlock, TextWord, bbox_from_block, bidi, build_text_layouts,
+    build_words, cmp_f32, collect_text_blocks, get_page, open_pdf, same_row,
+};
+
+#[derive(Debug, Error)]
+pub enum OcrXmlError {
+    #[error("PDF error: {0}")]
+    Pdf(#[from] PdfError),
+    #[error("dpi must be a positive finite value, got {0}")]
+    InvalidDpi(f32),
+    #[error("page {0} has non-positive dimensions")]
+    PageGeometry(usize),
+}
+
+/// Output format of [`export_ocr_xml`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcrXmlFormat {
+    Hocr,
+    Alto,
+}
+
+/// Serialize the text of the given pages (all pages when `None`) as hOCR or ALTO XML.
+pub fn export_ocr_xml(
+    path: &str,
+    pages: Option<&[usize]>,
+    dpi: f32,
+    format: OcrXmlFormat,
+) -> Result<String, OcrXmlError> {
+    if !dpi.is_finite() || dpi <= 0.0 {
+        return Err(OcrXmlError::InvalidDpi(dpi));
+    }
+    let pdf = open_pdf(path)?;
+    let page_indices: Vec<usize> = match pages {
+        Some(pages) => pages.to_vec(),
+        None => (0..pdf.num_pages() as usize).collect(),
+    };
+    let scale = f64::from(dpi) / 72.0;
+    let mut ocr_pages = Vec::with_capacity(page_indices.len());
+    for page_index in page_indices {
+        ocr_pages.push(read_page(&pdf, page_index, scale)?);
+    }
+    Ok(match format {
+        OcrXmlFormat::Hocr => write_hocr(path, &ocr_pages, dpi),
+        OcrXmlFormat::Alto => write_alto(path, &ocr_pages),
+    })
+}
+
+/// Bounding box in pixels: left, top, right, bottom.
+type PixelBox = (u32, u32, u32, u32);
+
+struct OcrWord {
+    text: String,
+    bbox: PixelBox,
+    /// Font size in points.
+    font_size: f32,
+    /// Offset of the word origin along the writing direction of its block, in user space.
+    position: f32,
+}
+
+struct OcrLine {
+    bbox: PixelBox,
+    /// Font size in pixels.
+    x_size: f32,
+    words: Vec<OcrWord>,
+}
+
+struct OcrBlock {
+    bbox: PixelBox,
+    lines: Vec<OcrLine>,
+}
+
+struct OcrPage {
+    page_index: usize,
+    width: u32,
+    height: u32,
+    blocks: Vec<OcrBlock>,
+}
+
+fn read_page(
+    pdf: &CachedFile<Vec<u8>>,
+    page_index: usize,
+    scale: f64,
+) -> Result<OcrPage, OcrXmlError> {
+    let page = get_page(pdf, page_index)?;
+    let page_ref: &PdfPage = &page;
+    let geometry = PageGeometry::from_pdf_page(page_ref, scale)
+        .map_err(|_| OcrXmlError::PageGeometry(page_index))?;
+    let (width, height) = geometry.pixel_size();
+    let mut ocr_page = OcrPage {
+        page_index,
+        width: width.round() as u32,
+        height: height.round() as u32,
+        blocks: Vec::new(),
+    };
+    let resolver = pdf.resolver();
+    let resources = page_ref.resources().ok();
+    let Some(content) = &page_ref.contents else {
+        return Ok(ocr_page);
+    };
+    let operations = content.operations(&resolver)?;
+    let blocks = collect_text_blocks(&operations, resources, &resolver)?;
+    let layouts = build_text_layouts(&blocks);
+    let words = build_words(&blocks);
+    let mut block_words: Vec<Vec<&TextWord>> = vec![Vec::new(); blocks.len()];
+    for word in &words {
+        block_words[word.block_index].push(word);
+    }
+
+    for layout in &layouts {
+        let mut lines: Vec<OcrLine> = Vec::new();
+        let mut previous: Option<&TextBlock> = None;
+        for &block_index in &layout.block_indices {
+            let block = &blocks[block_index];
+            if block_words[block_index].is_empty() {
+                continue;
+            }
+            let (dx, dy) = block
+                .glyphs
+                .first()
+                .map_or((1.0, 0.0), |glyph| glyph.direction);
+            let words = block_words[block_index].iter().map(|word| OcrWord {
+                text: word.text.clone(),
+                bbox: pixel_box(&geometry, word.bbox),
+                font_size: word.font_size,
+                position: word.origin_x * dx + word.origin_y * dy,
+            });
+            let bbox = pixel_box(&geometry, bbox_from_block(block));
+            let x_size = block.font_size.abs() * scale as f32;
+            match lines.last_mut() {
+                Some(line) if previous.is_some_and(|previous| same_row(previous, block)) => {
+                    line.bbox = pixel_union(line.bbox, bbox);
+                    line.x_size = line.x_size.max(x_size);
+                    line.words.extend(words);
+                }
+                _ => lines.push(OcrLine {
+                    bbox,
+                    x_size,
+                    words: words.collect(),
+                }),
+            }
+            previous = Some(block);
+        }
+        for line in &mut lines {
+            sort_line_words(&mut line.words);
+        }
+        if !lines.is_empty() {
+            ocr_page.blocks.push(OcrBlock {
+                bbox: pixel_box(&geometry, layout.bbox),
+                lines,
+            });
+        }
+    }
+    Ok(ocr_page)
+}
+
+/// Put the words of a line in reading order. Blocks of one row are listed in the layout by
+/// height, not by position, so the words are ordered along the writing direction, right to
+/// left when the row is mostly right-to-left script.
+fn sort_line_words(words: &mut [OcrWord]) {
+    words.sort_by(|a, b| cmp_f32(a.position, b.position));
+    let text: String = words.iter().map(|word| word.text.as_str()).collect();
+    if bidi::is_rtl_dominant(&text) {
+        words.reverse();
+    }
+}
+
+fn pixel_box(geometry: &PageGeometry, bbox: BBox) -> PixelBox {
+    let corners = [
+        (bbox.0, bbox.1),
+        (bbox.2, bbox.1),
+        (bbox.0, bbox.3),
+        (bbox.2, bbox.3),
+    ];
+    let (x0, y0, x1, y1) = corners.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(x0, y0, x1, y1), &(x, y)| {
+            let (px, py) = geometry.to_pixels(x as f64, y as f64);
+            (x0.min(px), y0.min(py), x1.max(px), y1.max(py))
+        },
+    );
+    (
+        x0.floor().max(0.0) as u32,
+        y0.floor().max(0.0) as u32,
+        x1.ceil().max(0.0) as u32,
+        y1.ceil().max(0.0) as u32,
+    )
+}
+
+fn pixel_union(a: PixelBox, b: PixelBox) -> PixelBox {
+    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn hocr_bbox((x0, y0, x1, y1): PixelBox) -> String {
+    format!("bbox {} {} {} {}", x0, y0, x1, y1)
+}
+
+fn write_hocr(source: &str, pages: &[OcrPage], dpi: f32) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \
+         \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">\n",
+    );
+    out.push_str("<html xmlns=\"http://www.w3.org/1999/xhtml\">\n<head>\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_xml(source)));
+    out.push_str("<meta http-equiv=\"Content-Type\" content=\"text/html;charset=utf-8\"/>\n");
+    out.push_str(&format!(
+        "<meta name=\"ocr-system\" content=\"pdfvectorizer {}\"/>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str(
+        "<meta name=\"ocr-capabilities\" content=\"ocr_page ocr_carea ocr_par ocr_line ocrx_word\"/>\n",
+    );
+    out.push_str("</head>\n<body>\n");
+    for page in pages {
+        let number = page.page_index + 1;
+        let title = format!(
+            "image \"{}\"; {}; ppageno {}; scan_res {} {}",
+            source,
+            hocr_bbox((0, 0, page.width, page.height)),
+            page.page_index,
+            dpi.round(),
+            dpi.round()
+        );
+        out.push_str(&format!(
+            "<div class=\"ocr_page\" id=\"page_{}\" title=\"{}\">\n",
+            number,
+            escape_xml(&title)
+        ));
+        let (mut line_id, mut word_id) = (0, 0);
+        for (index, block) in page.blocks.iter().enumerate() {
+            let block_id = index + 1;
+            let bbox = hocr_bbox(block.bbox);
+            out.push_str(&format!(
+                "<div class=\"ocr_carea\" id=\"block_{number}_{block_id}\" title=\"{bbox}\">\n"
+            ));
+            out.push_str(&format!(
+                "<p class=\"ocr_par\" id=\"par_{number}_{block_id}\" title=\"{bbox}\">\n"
+            ));
+            for line in &block.lines {
+                line_id += 1;
+                out.push_str(&format!(
+                    "<span class=\"ocr_line\" id=\"line_{}_{}\" title=\"{}; x_size {:.1}\">",
+                    number,
+                    line_id,
+                    hocr_bbox(line.bbox),
+                    line.x_size
+                ));
+                for (index, word) in line.words.iter().enumerate() {
+                    word_id += 1;
+                    if index > 0 {
+                        out.push(' ');
+                    }
+                    out.push_str(&format!(
+                        "<span class=\"ocrx_word\" id=\"word_{}_{}\" title=\"{}; x_fsize {:.1}\">{}</span>",
+                        number,
+                        word_id,
+                        hocr_bbox(word.bbox),
+                        word.font_size,
+                        escape_xml(&word.text)
+                    ));
+                }
+                out.push_str("</span>\n");
+            }
+            out.push_str("</p>\n</div>\n");
+        }
+        out.push_str("</div>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn alto_position((x0, y0, x1, y1): PixelBox) -> String {
+    format!(
+        "HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+        x0,
+        y0,
+        x1.saturating_sub(x0),
+        y1.saturating_sub(y0)
+    )
+}
+
+fn write_alto(source: &str, pages: &[OcrPage]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<alto xmlns=\"http://www.loc.gov/standards/alto/ns-v4#\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://www.loc.gov/standards/alto/ns-v4# \
+         http://www.loc.gov/alto/v4/alto-4-2.xsd\">\n",
+    );
+    out.push_str("  <Description>\n    <MeasurementUnit>pixel</MeasurementUnit>\n");
+    out.push_str(&format!(
+        "    <sourceImageInformation>\n      <fileName>{}</fileName>\n    </sourceImageInformation>\n",
+        escape_xml(source)
+    ));
+    out.push_str(&format!(
+        "    <OCRProcessing ID=\"OCR_0\">\n      <ocrProcessingStep>\n        <processingSoftware>\n          \
+         <softwareName>pdfvectorizer</softwareName>\n          <softwareVersion>{}</softwareVersion>\n        \
+         </processingSoftware>\n      </ocrProcessingStep>\n    </OCRProcessing>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str("  </Description>\n  <Layout>\n");
+    for page in pages {
+        let number = page.page_index + 1;
+        out.push_str(&format!(
+            "    <Page ID=\"page_{0}\" PHYSICAL_IMG_NR=\"{0}\" WIDTH=\"{1}\" HEIGHT=\"{2}\">\n",
+            number, page.width, page.height
+        ));
+        out.push_str(&format!(
+            "      <PrintSpace {}>\n",
+            alto_position((0, 0, page.width, page.height))
+        ));
+        let (mut line_id, mut word_id) = (0, 0);
+        for (index, block) in page.blocks.iter().enumerate() {
+            let block_id = index + 1;
+            out.push_str(&format!(
+                "        <TextBlock ID=\"block_{}_{}\" {}>\n",
+                number,
+                block_id,
+                alto_position(block.bbox)
+            ));
+            for line in &block.lines {
+                line_id += 1;
+                out.push_str(&format!(
+                    "          <TextLine ID=\"line_{}_{}\" {}>\n",
+                    number,
+                    line_id,
+                    alto_position(line.bbox)
+                ));
+                let mut previous: Option<PixelBox> = None;
+                for word in &line.words {
+                    word_id += 1;
+                    if let Some(previous) = previous {
+                        // The gap lies left of the word in right-to-left lines.
+                        let (left, right) = if word.bbox.0 >= previous.0 {
+                            (previous.2, word.bbox.0)
+                        } else {
+                            (word.bbox.2, previous.0)
+                        };
+                        // SP carries no height in ALTO.
+                        out.push_str(&format!(
+                            "            <SP HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\"/>\n",
+                            left,
+                            previous.1,
+                            right.saturating_sub(left)
+                        ));
+                    }
+                    out.push_str(&format!(
+                        "            <String ID=\"word_{}_{}\" {} CONTENT=\"{}\"/>\n",
+                        number,
+                        word_id,
+                        alto_position(word.bbox),
+                        escape_xml(&word.text)
+                    ));
+                    previous = Some(word.bbox);
+                }
+                out.push_str("          </TextLine>\n");
+            }
+            out.push_str("        </TextBlock>\n");
+        }
+        out.push_str("      </PrintSpace>\n    </Page>\n");
+    }
+    out.push_str("  </Layout>\n</alto>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, position: f32) -> OcrWord {
+        OcrWord {
+            text: text.to_owned(),
+            bbox: (0, 0, 0, 0),
+            font_size: 10.0,
+            position,
+        }
+    }
+
+    fn texts(words: &[OcrWord]) -> Vec<&str> {
+        words.iter().map(|word| word.text.as_str()).collect()
+    }
+
+    #[test]
+    fn escapes_markup_characters() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn drops_control_characters() {
+        assert_eq!(escape_xml("a\u{0}b\u{1b}c\u{7f}d"), "abcd");
+        assert_eq!(escape_xml("a\tb\nc\rd"), "a\tb\nc\rd");
+    }
+
+    #[test]
+    fn sorts_words_along_the_line() {
+        let mut words = vec![word("line", 30.0), word("A", 0.0), word("text", 10.0)];
+        sort_line_words(&mut words);
+        assert_eq!(texts(&words), ["A", "text", "line"]);
+    }
+
+    #[test]
+    fn sorts_right_to_left_words_from_the_right() {
+        let mut words = vec![word("שלום", 0.0), word("עולם", 40.0), word("1", 20.0)];
+        sort_line_words(&mut words);
+        assert_eq!(texts(&words), ["עולם", "1", "שלום"]);
+    }
+}
@@ -0,0 +1,155 @@
+//! Text search over the glyphs of a page.
+//!
+//! The page text is rebuilt from glyphs in content order and NFKC-normalized, so ligatures and
+//! full-width/half-width forms compare equal to their plain spellings. A space is inserted
+//! wherever the glyphs break a word or a line, except between two CJK characters. Each
+//! character remembers the glyph it came from, so a match maps back to glyph boxes and is
+//! reported as one rectangle per text block and line.
+
+use regex::{Regex, RegexBuilder};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{BBox, TextBlock, TextGlyph, bbox_union, continues_word, shares_baseline};
+
+/// A match on one page.
+pub struct SearchHit {
+    /// Matched text, as normalized for searching.
+    pub text: String,
+    pub rects: Vec<BBox>,
+}
+
+/// Compile a query, literal unless `regex` is set, with whitespace matching any run of spaces.
+pub fn compile_query(
+    query: &str,
+    case_sensitive: bool,
+    regex: bool,
+) -> Result<Regex, regex::Error> {
+    let normalized: String = query.nfkc().collect();
+    let pattern = if regex {
+        normalized
+    } else {
+        normalized
+            .split_whitespace()
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(r"\s+")
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+}
+
+/// Searchable text of a page with the glyph behind each character.
+struct PageText<'a> {
+    text: String,
+    /// Byte offset of every character in `text` and the index of its glyph; inserted spaces
+    /// have none.
+    offsets: Vec<(usize, Option<usize>)>,
+    /// Glyphs in content order with the index of their text block.
+    glyphs: Vec<(usize, &'a TextGlyph)>,
+}
+
+impl<'a> PageText<'a> {
+    fn new(blocks: &'a [TextBlock]) -> Self {
+        let glyphs: Vec<(usize, &TextGlyph)> = blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(block_index, block)| block.glyphs.iter().map(move |g| (block_index, g)))
+            .collect();
+        let mut page = Self {
+            text: String::new(),
+            offsets: Vec::new(),
+            glyphs: Vec::new(),
+        };
+        let mut previous: Option<&TextGlyph> = None;
+        for (index, &(_, glyph)) in glyphs.iter().enumerate() {
+            let normalized: String = glyph.text.nfkc().collect();
+            if normalized.trim().is_empty() {
+                page.push_space();
+                previous = None;
+                continue;
+            }
+            let joins_cjk = page.text.chars().next_back().is_some_and(is_cjk)
+                && normalized.chars().next().is_some_and(is_cjk);
+            if previous.is_some_and(|last| !continues_word(last, glyph)) && !joins_cjk {
+                page.push_space();
+            }
+            for ch in normalized.chars() {
+                page.offsets.push((page.text.len(), Some(index)));
+                page.text.push(ch);
+            }
+            previous = Some(glyph);
+        }
+        page.glyphs = glyphs;
+        page
+    }
+
+    fn push_space(&mut self) {
+        if !self.text.is_empty() && !self.text.ends_with(' ') {
+            self.offsets.push((self.text.len(), None));
+            self.text.push(' ');
+        }
+    }
+
+    /// Glyph indices behind the byte range `start..end`, without repeats.
+    fn glyphs_in(&self, start: usize, end: usize) -> Vec<usize> {
+        let first = self.offsets.partition_point(|&(offset, _)| offset < start);
+        let mut indices: Vec<usize> = self.offsets[first..]
+            .iter()
+            .take_while(|&&(offset, _)| offset < end)
+            .filter_map(|&(_, glyph)| glyph)
+            .collect();
+        indices.dedup();
+        indices
+    }
+
+    /// One rectangle per run of glyphs that stays in one text block and on one baseline.
+    fn rects(&self, indices: &[usize]) -> Vec<BBox> {
+        let mut rects: Vec<BBox> = Vec::new();
+        let mut previous: Option<(usize, &TextGlyph)> = None;
+        for &index in indices {
+            let (block_index, glyph) = self.glyphs[index];
+            let bbox = (glyph.x0, glyph.y0, glyph.x1, glyph.y1);
+            match (previous, rects.last_mut()) {
+                (Some((last_block, last)), Some(rect))
+                    if last_block == block_index && shares_baseline(last, glyph) =>
+                {
+                    *rect = bbox_union(*rect, bbox);
+                }
+                _ => rects.push(bbox),
+            }
+            previous = Some((block_index, glyph));
+        }
+        rects
+    }
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3000}'..='\u{303F}'
+            | '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+    )
+}
+
+/// Find every non-empty match of `pattern` in the text of `blocks`.
+pub fn search_blocks(blocks: &[TextBlock], pattern: &Regex) -> Vec<SearchHit> {
+    let page = PageText::new(blocks);
+    pattern
+        .find_iter(&page.text)
+        .filter(|found| !found.is_empty())
+        .filter_map(|found| {
+            let indices = page.glyphs_in(found.start(), found.end());
+            if indices.is_empty() {
+                return None;
+            }
+            Some(SearchHit {
+                text: found.as_str().to_owned(),
+                rects: page.rects(&indices),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,205 @@
+//! Painted paths as ordered subpaths, for `extract_shapes`.
+//!
+//! `walk_path_ops` records the construction operators of the path under way as
+//! [`PathCommand`]s next to the flat segments used by the layout analysis. When the path is
+//! stroked or filled the commands become one [`PathShape`], split into subpaths at each
+//! `moveto`, so compound shapes such as a glyph outline with holes stay one record. A segment
+//! drawn after `closepath` starts a new subpath at the start of the closed one, as in PDF.
+
+use crate::{BBox, PathStyle, points_bbox};
+
+/// A path construction command with its points in user space.
+#[derive(Debug, Clone)]
+pub enum PathCommand {
+    MoveTo((f32, f32)),
+    LineTo((f32, f32)),
+    CurveTo((f32, f32), (f32, f32), (f32, f32)),
+    Close,
+}
+
+impl PathCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PathCommand::MoveTo(_) => "move",
+            PathCommand::LineTo(_) => "line",
+            PathCommand::CurveTo(..) => "cubic",
+            PathCommand::Close => "close",
+        }
+    }
+
+    pub fn points(&self) -> Vec<(f32, f32)> {
+        match *self {
+            PathCommand::MoveTo(p) | PathCommand::LineTo(p) => vec![p],
+            PathCommand::CurveTo(c1, c2, p) => vec![c1, c2, p],
+            PathCommand::Close => Vec::new(),
+        }
+    }
+
+    pub fn map_points(&mut self, mut map: impl FnMut((f32, f32)) -> (f32, f32)) {
+        match self {
+            PathCommand::MoveTo(p) | PathCommand::LineTo(p) => *p = map(*p),
+            PathCommand::CurveTo(c1, c2, p) => {
+                *c1 = map(*c1);
+                *c2 = map(*c2);
+                *p = map(*p);
+            }
+            PathCommand::Close => {}
+        }
+    }
+}
+
+/// One painted path: its subpaths, each starting with a `MoveTo`, and its style.
+pub struct PathShape {
+    pub subpaths: Vec<Vec<PathCommand>>,
+    pub style: PathStyle,
+    pub mcid: Option<u32>,
+}
+
+impl PathShape {
+    pub fn new(commands: Vec<PathCommand>, style: PathStyle, mcid: Option<u32>) -> Self {
+        let mut subpaths: Vec<Vec<PathCommand>> = Vec::new();
+        // Start of the current subpath, which becomes the current point after a close.
+        let mut start: Option<(f32, f32)> = None;
+        for command in commands {
+            if let PathCommand::MoveTo(point) = command {
+                start = Some(point);
+                subpaths.push(vec![command]);
+                continue;
+            }
+            let open = subpaths
+                .last()
+                .is_some_and(|subpath| !matches!(subpath.last(), Some(PathCommand::Close)));
+            if !open {
+                match start {
+                    Some(point) if !matches!(command, PathCommand::Close) => {
+                        subpaths.push(vec![PathCommand::MoveTo(point)]);
+                    }
+                    // Closing again does nothing, and without a current point nothing is drawn.
+                    _ => continue,
+                }
+            }
+            if let Some(subpath) = subpaths.last_mut() {
+                subpath.push(command);
+            }
+        }
+        // A lone moveto draws nothing.
+        subpaths.retain(|subpath| subpath.len() > 1);
+        Self {
+            subpaths,
+            style,
+            mcid,
+        }
+    }
+
+    pub fn bbox(&self) -> Option<BBox> {
+        let points: Vec<(f32, f32)> = self
+            .subpaths
+            .iter()
+            .flatten()
+            .flat_map(PathCommand::points)
+            .collect();
+        points_bbox(&points)
+    }
+
+    pub fn map_points(&mut self, mut map: impl FnMut((f32, f32)) -> (f32, f32)) {
+        for command in self.subpaths.iter_mut().flatten() {
+            command.map_points(&mut map);
+        }
+    }
+
+    /// SVG path data (`d` attribute) of all subpaths.
+    pub fn svg_path_data(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        for command in self.subpaths.iter().flatten() {
+            parts.push(match *command {
+                PathCommand::MoveTo((x, y)) => format!("M{} {}", svg_number(x), svg_number(y)),
+                PathCommand::LineTo((x, y)) => format!("L{} {}", svg_number(x), svg_number(y)),
+                PathCommand::CurveTo((x1, y1), (x2, y2), (x, y)) => format!(
+                    "C{} {} {} {} {} {}",
+                    svg_number(x1),
+                    svg_number(y1),
+                    svg_number(x2),
+                    svg_number(y2),
+                    svg_number(x),
+                    svg_number(y)
+                ),
+                PathCommand::Close => "Z".to_string(),
+            });
+        }
+        parts.join(" ")
+    }
+}
+
+/// Format a coordinate with at most three decimals and no trailing zeros.
+pub fn svg_number(value: f32) -> String {
+    let formatted = format!("{:.3}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PathCommand::{Close, LineTo, MoveTo};
+
+    fn shape(commands: Vec<PathCommand>) -> PathShape {
+        PathShape::new(commands, PathStyle::default(), None)
+    }
+
+    #[test]
+    fn splits_subpaths_at_each_move() {
+        let shape = shape(vec![
+            MoveTo((0.0, 0.0)),
+            LineTo((1.0, 0.0)),
+            MoveTo((2.0, 0.0)),
+            LineTo((3.0, 0.0)),
+        ]);
+        assert_eq!(shape.subpaths.len(), 2);
+        assert_eq!(shape.svg_path_data(), "M0 0 L1 0 M2 0 L3 0");
+    }
+
+    #[test]
+    fn segment_after_close_starts_at_the_closed_subpath() {
+        let shape = shape(vec![
+            MoveTo((0.0, 0.0)),
+            LineTo((1.0, 0.0)),
+            LineTo((1.0, 1.0)),
+            Close,
+            LineTo((2.0, 2.0)),
+        ]);
+        assert_eq!(shape.subpaths.len(), 2);
+        assert_eq!(shape.svg_path_data(), "M0 0 L1 0 L1 1 Z M0 0 L2 2");
+    }
+
+    #[test]
+    fn drops_segments_without_a_current_point() {
+        let shape = shape(vec![LineTo((1.0, 1.0)), Close, MoveTo((2.0, 2.0))]);
+        assert!(shape.subpaths.is_empty());
+        assert_eq!(shape.svg_path_data(), "");
+    }
+
+    #[test]
+    fn repeated_close_adds_nothing() {
+        let shape = shape(vec![MoveTo((0.0, 0.0)), LineTo((1.0, 0.0)), Close, Close]);
+        assert_eq!(shape.svg_path_data(), "M0 0 L1 0 Z");
+    }
+
+    #[test]
+    fn svg_number_rounds_to_three_decimals() {
+        assert_eq!(svg_number(1.0), "1");
+        assert_eq!(svg_number(0.5), "0.5");
+        assert_eq!(svg_number(1.23456), "1.235");
+        assert_eq!(svg_number(-2.1004), "-2.1");
+        assert_eq!(svg_number(100.0), "100");
+    }
+
+    #[test]
+    fn svg_number_has_no_negative_zero() {
+        assert_eq!(svg_number(-0.0), "0");
+        assert_eq!(svg_number(-0.0004), "0");
+        assert_eq!(svg_number(0.0), "0");
+    }
+}
@@ -0,0 +1,485 @@
+//! Built-in metrics for the 14 standard PDF fonts.
+//!
+//! Non-embedded Helvetica, Times, Courier, Symbol and ZapfDingbats fonts are allowed to omit
+//! `/Widths` and `/FontDescriptor`, so the AFM widths and ascent/descent are bundled here.
+//! Widths are in glyph units (1/1000 em). The tables cover the printable ASCII range of each
+//! font's built-in encoding plus the common Latin punctuation and ligatures. Accented letters
+//! reuse the width of their base letter unless listed with their own AFM width: the accented
+//! dotless i of Helvetica is wider than `i`, and `dcaron` / `lcaron` / `tcaron` draw the caron
+//! as an apostrophe beside the letter.
+
+use crate::encodings::BuiltinEncoding;
+
+/// Metrics of one standard font.
+pub struct StandardFont {
+    pub name: &'static str,
+    pub ascent: f32,
+    pub descent: f32,
+    pub serif: bool,
+    pub fixed_pitch: bool,
+    builtin: fn(u8) -> Option<&'static str>,
+    widths: &'static [u16; 95],
+    extra: &'static [(&'static str, u16)],
+    missing_width: u16,
+}
+
+impl StandardFont {
+    /// Glyph name selected by `code` through the font's built-in encoding.
+    pub fn builtin_glyph_name(&self, code: u8) -> Option<&'static str> {
+        (self.builtin)(code)
+    }
+
+    /// Width of the glyph selected by `code` through the font's built-in encoding.
+    pub fn code_width(&self, code: u16) -> f32 {
+        if (32..=126).contains(&code) {
+            return self.widths[code as usize - 32] as f32;
+        }
+        u8::try_from(code)
+            .ok()
+            .and_then(|code| self.builtin_glyph_name(code))
+            .and_then(|name| self.glyph_width(name))
+            .unwrap_or(self.missing_width as f32)
+    }
+
+    /// Width of a glyph looked up by its PostScript glyph name.
+    pub fn glyph_width(&self, name: &str) -> Option<f32> {
+        if let Some(code) = (32..=126u8).find(|&code| self.builtin_glyph_name(code) == Some(name)) {
+            return Some(self.widths[code as usize - 32] as f32);
+        }
+        if let Some(&(_, width)) = self.extra.iter().find(|(candidate, _)| *candidate == name) {
+            return Some(width as f32);
+        }
+        accented_base(name).and_then(|base| self.glyph_width(base))
+    }
+}
+
+fn latin_builtin(code: u8) -> Option<&'static str> {
+    BuiltinEncoding::Standard.glyph_name(code)
+}
+
+fn symbol_builtin(code: u8) -> Option<&'static str> {
+    (32..=126)
+        .contains(&code)
+        .then(|| SYMBOL_ASCII_NAMES[code as usize - 32])
+}
+
+fn no_builtin(_code: u8) -> Option<&'static str> {
+    None
+}
+
+/// Find the standard font for a BaseFont name, accepting the common Arial, Times New Roman
+/// and Courier New aliases and `,Bold` style suffixes.
+pub fn lookup(base_font: &str) -> Option<&'static StandardFont> {
+    let compact: String = base_font.chars().filter(|c| !c.is_whitespace()).collect();
+    let (family, style) = match compact.find([',', '-']) {
+        Some(index) => (&compact[..index], compact[index + 1..].to_ascii_lowercase()),
+        None => (compact.as_str(), String::new()),
+    };
+    let bold = style.contains("bold");
+    let italic = style.contains("italic") || style.contains("oblique");
+    let name = match family {
+        "Helvetica" | "Arial" | "ArialMT" => match (bold, italic) {
+            (false, false) => "Helvetica",
+            (true, false) => "Helvetica-Bold",
+            (false, true) => "Helvetica-Oblique",
+            (true, true) => "Helvetica-BoldOblique",
+        },
+        "Times" | "TimesNewRoman" | "TimesNewRomanPS" | "TimesNewRomanPSMT" => {
+            match (bold, italic) {
+                (false, false) => "Times-Roman",
+                (true, false) => "Times-Bold",
+                (false, true) => "Times-Italic",
+                (true, true) => "Times-BoldItalic",
+            }
+        }
+        "Courier" | "CourierNew" | "CourierNewPS" | "CourierNewPSMT" => match (bold, italic) {
+            (false, false) => "Courier",
+            (true, false) => "Courier-Bold",
+            (false, true) => "Courier-Oblique",
+            (true, true) => "Courier-BoldOblique",
+        },
+        "Symbol" => "Symbol",
+        "ZapfDingbats" => "ZapfDingbats",
+        _ => return None,
+    };
+    STANDARD_FONTS.iter().find(|font| font.name == name)
+}
+
+const ACCENT_SUFFIXES: [&str; 14] = [
+    "acute",
+    "grave",
+    "circumflex",
+    "dieresis",
+    "tilde",
+    "ring",
+    "cedilla",
+    "caron",
+    "macron",
+    "breve",
+    "dotaccent",
+    "ogonek",
+    "hungarumlaut",
+    "commaaccent",
+];
+
+/// Base letter of an accented glyph name such as `eacute` or `Ccedilla`.
+fn accented_base(name: &str) -> Option<&str> {
+    ACCENT_SUFFIXES.iter().find_map(|suffix| {
+        name.strip_suffix(suffix)
+            .filter(|base| base.len() == 1 && base.chars().all(|c| c.is_ascii_alphabetic()))
+    })
+}
+
+static STANDARD_FONTS: [StandardFont; 14] = [
+    StandardFont {
+        name: "Helvetica",
+        ascent: 718.0,
+        descent: -207.0,
+        serif: false,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &HELVETICA_WIDTHS,
+        extra: HELVETICA_EXTRA,
+        missing_width: 556,
+    },
+    StandardFont {
+        name: "Helvetica-Bold",
+        ascent: 718.0,
+        descent: -207.0,
+        serif: false,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &HELVETICA_BOLD_WIDTHS,
+        extra: HELVETICA_BOLD_EXTRA,
+        missing_width: 556,
+    },
+    StandardFont {
+        name: "Helvetica-Oblique",
+        ascent: 718.0,
+        descent: -207.0,
+        serif: false,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &HELVETICA_WIDTHS,
+        extra: HELVETICA_EXTRA,
+        missing_width: 556,
+    },
+    StandardFont {
+        name: "Helvetica-BoldOblique",
+        ascent: 718.0,
+        descent: -207.0,
+        serif: false,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &HELVETICA_BOLD_WIDTHS,
+        extra: HELVETICA_BOLD_EXTRA,
+        missing_width: 556,
+    },
+    StandardFont {
+        name: "Times-Roman",
+        ascent: 683.0,
+        descent: -217.0,
+        serif: true,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &TIMES_ROMAN_WIDTHS,
+        extra: TIMES_ROMAN_EXTRA,
+        missing_width: 500,
+    },
+    StandardFont {
+        name: "Times-Bold",
+        ascent: 683.0,
+        descent: -217.0,
+        serif: true,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &TIMES_BOLD_WIDTHS,
+        extra: TIMES_BOLD_EXTRA,
+        missing_width: 500,
+    },
+    StandardFont {
+        name: "Times-Italic",
+        ascent: 683.0,
+        descent: -217.0,
+        serif: true,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &TIMES_ITALIC_WIDTHS,
+        extra: TIMES_ITALIC_EXTRA,
+        missing_width: 500,
+    },
+    StandardFont {
+        name: "Times-BoldItalic",
+        ascent: 683.0,
+        descent: -217.0,
+        serif: true,
+        fixed_pitch: false,
+        builtin: latin_builtin,
+        widths: &TIMES_BOLD_ITALIC_WIDTHS,
+        extra: TIMES_BOLD_ITALIC_EXTRA,
+        missing_width: 500,
+    },
+    StandardFont {
+        name: "Courier",
+        ascent: 629.0,
+        descent: -157.0,
+        serif: true,
+        fixed_pitch: true,
+        builtin: latin_builtin,
+        widths: &COURIER_WIDTHS,
+        extra: COURIER_EXTRA,
+        missing_width: 600,
+    },
+    StandardFont {
+        name: "Courier-Bold",
+        ascent: 629.0,
+        descent: -157.0,
+        serif: true,
+        fixed_pitch: true,
+        builtin: latin_builtin,
+        widths: &COURIER_WIDTHS,
+        extra: COURIER_EXTRA,
+        missing_width: 600,
+    },
+    StandardFont {
+        name: "Courier-Oblique",
+        ascent: 629.0,
+        descent: -157.0,
+        serif: true,
+        fixed_pitch: true,
+        builtin: latin_builtin,
+        widths: &COURIER_WIDTHS,
+        extra: COURIER_EXTRA,
+        missing_width: 600,
+    },
+    StandardFont {
+        name: "Courier-BoldOblique",
+        ascent: 629.0,
+        descent: -157.0,
+        serif: true,
+        fixed_pitch: true,
+        builtin: latin_builtin,
+        widths: &COURIER_WIDTHS,
+        extra: COURIER_EXTRA,
+        missing_width: 600,
+    },
+    StandardFont {
+        name: "Symbol",
+        ascent: 1010.0,
+        descent: -293.0,
+        serif: true,
+        fixed_pitch: false,
+        builtin: symbol_builtin,
+        widths: &SYMBOL_WIDTHS,
+        extra: &[],
+        missing_width: 500,
+    },
+    StandardFont {
+        name: "ZapfDingbats",
+        ascent: 820.0,
+        descent: -143.0,
+        serif: false,
+        fixed_pitch: false,
+        builtin: no_builtin,
+        widths: &ZAPF_DINGBATS_WIDTHS,
+        extra: &[],
+        missing_width: 788,
+    },
+];
+
+#[rustfmt::skip]
+const SYMBOL_ASCII_NAMES: [&str; 95] = [
+    "space", "exclam", "universal", "numbersign", "existential", "percent", "ampersand",
+    "suchthat", "parenleft", "parenright", "asteriskmath", "plus", "comma", "minus", "period",
+    "slash", "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "colon", "semicolon", "less", "equal", "greater", "question", "congruent", "Alpha", "Beta",
+    "Chi", "Delta", "Epsilon", "Phi", "Gamma", "Eta", "Iota", "theta1", "Kappa", "Lambda", "Mu",
+    "Nu", "Omicron", "Pi", "Theta", "Rho", "Sigma", "Tau", "Upsilon", "sigma1", "Omega", "Xi",
+    "Psi", "Zeta", "bracketleft", "therefore", "bracketright", "perpendicular", "underscore",
+    "radicalex", "alpha", "beta", "chi", "delta", "epsilon", "phi", "gamma", "eta", "iota", "phi1",
+    "kappa", "lambda", "mu", "nu", "omicron", "pi", "theta", "rho", "sigma", "tau", "upsilon",
+    "omega1", "omega", "xi", "psi", "zeta", "braceleft", "bar", "braceright", "similar",
+];
+
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 222, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 222, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 278, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 278, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+const TIMES_ROMAN_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 333, 333, 333, 500, 564, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444, 921, 722, 667, 667, 722, 611,
+    556, 722, 722, 333, 389, 722, 611, 889, 722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722,
+    722, 611, 333, 278, 333, 469, 500, 333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500,
+    278, 778, 500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+const TIMES_BOLD_WIDTHS: [u16; 95] = [
+    250, 333, 555, 500, 500, 1000, 833, 333, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 930, 722, 667, 722, 722, 667,
+    611, 778, 778, 389, 500, 778, 667, 944, 722, 778, 611, 778, 722, 556, 667, 722, 722, 1000, 722,
+    722, 667, 333, 278, 333, 581, 500, 333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556,
+    278, 833, 556, 500, 556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+
+const TIMES_ITALIC_WIDTHS: [u16; 95] = [
+    250, 333, 420, 500, 500, 833, 778, 333, 333, 333, 500, 675, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500, 920, 611, 611, 667, 722, 611,
+    611, 722, 722, 333, 444, 667, 556, 833, 667, 722, 611, 722, 611, 500, 556, 722, 611, 833, 611,
+    556, 556, 389, 278, 389, 422, 500, 333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444,
+    278, 722, 500, 500, 500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+];
+
+const TIMES_BOLD_ITALIC_WIDTHS: [u16; 95] = [
+    250, 389, 555, 500, 500, 833, 778, 333, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 832, 667, 667, 667, 722, 667,
+    667, 722, 778, 389, 500, 667, 611, 889, 722, 722, 611, 722, 667, 556, 611, 722, 667, 889, 667,
+    611, 611, 333, 278, 333, 570, 500, 333, 500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500,
+    278, 778, 556, 500, 500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, 348, 220, 348, 570,
+];
+
+const COURIER_WIDTHS: [u16; 95] = [
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+    600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600, 600,
+];
+
+const SYMBOL_WIDTHS: [u16; 95] = [
+    250, 333, 713, 500, 549, 833, 778, 439, 333, 333, 500, 549, 250, 549, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 278, 278, 549, 549, 549, 444, 549, 722, 667, 722, 612, 611,
+    763, 603, 722, 333, 631, 722, 686, 889, 722, 722, 768, 741, 556, 592, 611, 690, 439, 768, 645,
+    795, 611, 333, 863, 333, 658, 500, 500, 631, 549, 549, 494, 439, 521, 411, 603, 329, 603, 549,
+    549, 576, 521, 549, 549, 521, 549, 603, 439, 576, 713, 686, 493, 686, 494, 480, 200, 480, 549,
+];
+
+const ZAPF_DINGBATS_WIDTHS: [u16; 95] = [
+    278, 974, 961, 974, 980, 719, 789, 790, 791, 690, 960, 939, 549, 855, 911, 933, 911, 945, 974,
+    755, 846, 762, 761, 571, 677, 763, 760, 759, 754, 494, 552, 537, 577, 692, 786, 788, 788, 790,
+    793, 794, 816, 823, 789, 841, 823, 833, 816, 831, 923, 744, 723, 749, 790, 792, 695, 776, 768,
+    792, 759, 707, 708, 682, 701, 826, 815, 789, 789, 707, 687, 696, 689, 786, 787, 713, 791, 785,
+    791, 873, 761, 762, 762, 759, 759, 892, 892, 788, 784, 438, 138, 277, 415, 392, 392, 668, 668,
+];
+
+#[rustfmt::skip]
+const HELVETICA_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 191), ("grave", 333), ("quotedblleft", 333), ("quotedblright", 333),
+    ("quotesinglbase", 222), ("quotedblbase", 333), ("endash", 556), ("emdash", 1000),
+    ("bullet", 350), ("ellipsis", 1000), ("dagger", 556), ("daggerdbl", 556), ("fi", 500),
+    ("fl", 500), ("guillemotleft", 556), ("guillemotright", 556), ("guilsinglleft", 333),
+    ("guilsinglright", 333), ("exclamdown", 333), ("questiondown", 611), ("cent", 556),
+    ("sterling", 556), ("yen", 556), ("section", 556), ("copyright", 737), ("registered", 737),
+    ("degree", 400), ("germandbls", 611), ("AE", 1000), ("ae", 889), ("OE", 1000), ("oe", 944),
+    ("Oslash", 778), ("oslash", 611), ("dotlessi", 278), ("perthousand", 1000),
+    ("trademark", 1000), ("multiply", 584), ("divide", 584), ("plusminus", 584),
+    ("periodcentered", 278), ("paragraph", 537),
+    // Accented letters whose AFM width differs from their base letter.
+    ("iacute", 278), ("icircumflex", 278), ("idieresis", 278), ("igrave", 278),
+    ("imacron", 278), ("dcaron", 643), ("lcaron", 299), ("tcaron", 317),
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 238), ("grave", 333), ("quotedblleft", 500), ("quotedblright", 500),
+    ("quotesinglbase", 278), ("quotedblbase", 500), ("endash", 556), ("emdash", 1000),
+    ("bullet", 350), ("ellipsis", 1000), ("dagger", 556), ("daggerdbl", 556), ("fi", 611),
+    ("fl", 611), ("guillemotleft", 556), ("guillemotright", 556), ("guilsinglleft", 333),
+    ("guilsinglright", 333), ("exclamdown", 333), ("questiondown", 611), ("cent", 556),
+    ("sterling", 556), ("yen", 556), ("section", 556), ("copyright", 737), ("registered", 737),
+    ("degree", 400), ("germandbls", 611), ("AE", 1000), ("ae", 889), ("OE", 1000), ("oe", 944),
+    ("Oslash", 778), ("oslash", 611), ("dotlessi", 278), ("perthousand", 1000),
+    ("trademark", 1000), ("multiply", 584), ("divide", 584), ("plusminus", 584),
+    ("periodcentered", 278), ("paragraph", 556),
+    // Accented letters whose AFM width differs from their base letter.
+    ("dcaron", 743), ("lcaron", 400), ("tcaron", 389),
+];
+
+#[rustfmt::skip]
+const TIMES_ROMAN_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 180), ("grave", 333), ("quotedblleft", 444), ("quotedblright", 444),
+    ("quotesinglbase", 333), ("quotedblbase", 444), ("endash", 500), ("emdash", 1000),
+    ("bullet", 350), ("ellipsis", 1000), ("dagger", 500), ("daggerdbl", 500), ("fi", 556),
+    ("fl", 556), ("guillemotleft", 500), ("guillemotright", 500), ("guilsinglleft", 333),
+    ("guilsinglright", 333), ("exclamdown", 333), ("questiondown", 444), ("cent", 500),
+    ("sterling", 500), ("yen", 500), ("section", 500), ("copyright", 760), ("registered", 760),
+    ("degree", 400), ("germandbls", 500), ("AE", 889), ("ae", 667), ("OE", 889), ("oe", 722),
+    ("Oslash", 722), ("oslash", 500), ("dotlessi", 278), ("perthousand", 1000), ("trademark", 980),
+    ("multiply", 564), ("divide", 564), ("plusminus", 564), ("periodcentered", 250),
+    ("paragraph", 453),
+    // Accented letters whose AFM width differs from their base letter.
+    ("dcaron", 588), ("lcaron", 344), ("tcaron", 326),
+];
+
+#[rustfmt::skip]
+const TIMES_BOLD_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 278), ("grave", 333), ("quotedblleft", 500), ("quotedblright", 500),
+    ("quotesinglbase", 333), ("quotedblbase", 500), ("endash", 500), ("emdash", 1000),
+    ("bullet", 350), ("ellipsis", 1000), ("dagger", 500), ("daggerdbl", 500), ("fi", 556),
+    ("fl", 556), ("guillemotleft", 500), ("guillemotright", 500), ("guilsinglleft", 333),
+    ("guilsinglright", 333), ("exclamdown", 333), ("questiondown", 500), ("cent", 500),
+    ("sterling", 500), ("yen", 500), ("section", 500), ("copyright", 747), ("registered", 747),
+    ("degree", 400), ("germandbls", 556), ("AE", 1000), ("ae", 722), ("OE", 1000), ("oe", 722),
+    ("Oslash", 778), ("oslash", 500), ("dotlessi", 278), ("perthousand", 1000),
+    ("trademark", 1000), ("multiply", 570), ("divide", 570), ("plusminus", 570),
+    ("periodcentered", 250), ("paragraph", 540),
+    // Accented letters whose AFM width differs from their base letter.
+    ("dcaron", 672), ("lcaron", 394), ("tcaron", 416),
+];
+
+#[rustfmt::skip]
+const TIMES_ITALIC_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 214), ("grave", 333), ("quotedblleft", 556), ("quotedblright", 556),
+    ("quotesinglbase", 333), ("quotedblbase", 556), ("endash", 500), ("emdash", 889),
+    ("bullet", 350), ("ellipsis", 889), ("dagger", 500), ("daggerdbl", 500), ("fi", 500),
+    ("fl", 500), ("guillemotleft", 500), ("guillemotright", 500), ("guilsinglleft", 333),
+    ("guilsinglright", 333), ("exclamdown", 389), ("questiondown", 500), ("cent", 500),
+    ("sterling", 500), ("yen", 500), ("section", 500), ("copyright", 760), ("registered", 760),
+    ("degree", 400), ("germandbls", 500), ("AE", 889), ("ae", 667), ("OE", 944), ("oe", 667),
+    ("Oslash", 722), ("oslash", 500), ("dotlessi", 278), ("perthousand", 1000), ("trademark", 980),
+    ("multiply", 675), ("divide", 675), ("plusminus", 675), ("periodcentered", 250),
+    ("paragraph", 523),
+    // Accented letters whose AFM width differs from their base letter.
+    ("Lcaron", 611), ("dcaron", 544), ("lcaron", 300), ("tcaron", 300),
+];
+
+#[rustfmt::skip]
+const TIMES_BOLD_ITALIC_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 278), ("grave", 333), ("quotedblleft", 500), ("quotedblright", 500),
+    ("quotesinglbase", 333), ("quotedblbase", 500), ("endash", 500), ("emdash", 1000),
+    ("bullet", 350), ("ellipsis", 1000), ("dagger", 500), ("daggerdbl", 500), ("fi", 556),
+    ("fl", 556), ("guillemotleft", 500), ("guillemotright", 500), ("guilsinglleft", 333),
+    ("guilsinglright", 333), ("exclamdown", 389), ("questiondown", 500), ("cent", 500),
+    ("sterling", 500), ("yen", 500), ("section", 500), ("copyright", 747), ("registered", 747),
+    ("degree", 400), ("germandbls", 500), ("AE", 944), ("ae", 722), ("OE", 944), ("oe", 722),
+    ("Oslash", 722), ("oslash", 500), ("dotlessi", 278), ("perthousand", 1000),
+    ("trademark", 1000), ("multiply", 570), ("divide", 570), ("plusminus", 570),
+    ("periodcentered", 250), ("paragraph", 500),
+    // Accented letters whose AFM width differs from their base letter.
+    ("dcaron", 608), ("lcaron", 382), ("tcaron", 366),
+];
+
+#[rustfmt::skip]
+const COURIER_EXTRA: &[(&str, u16)] = &[
+    ("quotesingle", 600), ("grave", 600), ("quotedblleft", 600), ("quotedblright", 600),
+    ("quotesinglbase", 600), ("quotedblbase", 600), ("endash", 600), ("emdash", 600),
+    ("bullet", 600), ("ellipsis", 600), ("dagger", 600), ("daggerdbl", 600), ("fi", 600),
+    ("fl", 600), ("guillemotleft", 600), ("guillemotright", 600), ("guilsinglleft", 600),
+    ("guilsinglright", 600), ("exclamdown", 600), ("questiondown", 600), ("cent", 600),
+    ("sterling", 600), ("yen", 600), ("section", 600), ("copyright", 600), ("registered", 600),
+    ("degree", 600), ("germandbls", 600), ("AE", 600), ("ae", 600), ("OE", 600), ("oe", 600),
+    ("Oslash", 600), ("oslash", 600), ("dotlessi", 600), ("perthousand", 600), ("trademark", 600),
+    ("multiply", 600), ("divide", 600), ("plusminus", 600), ("periodcentered", 600),
+    ("paragraph", 600),
+];
@@ -0,0 +1,381 @@
+//! Tagged PDF: marked content in content streams and the logical structure tree.
+//!
+//! Content streams bracket content with `BMC`/`BDC` ... `EMC`. A `BDC` property list (inline
+//! or named in the `/Properties` resource) may carry an `/MCID`, which links the content to a
+//! structure element, and an `/ActualText` that replaces the text shown inside it. The walkers
+//! track the open sequences with [`MarkedContentStack`] and tag what they produce with the
+//! innermost MCID. MCIDs inside a Form XObject number the form's own stream rather than the
+//! page, so they are not used for tagging.
+//!
+//! The structure tree hangs off `/StructTreeRoot` in the catalog. Elements carry a role in
+//! `/S` (mapped to a standard role through `/RoleMap`) and kids in `/K`: other elements,
+//! MCIDs of their page (`/Pg`, inherited from ancestors), marked-content references and
+//! object references such as annotations. Marked-content references into a form's stream
+//! (`/Stm`) are skipped, matching the walkers.
+
+use std::collections::{HashMap, HashSet};
+
+use pdf::error::PdfError;
+use pdf::object::{MaybeRef, PlainRef, Resolve, Resources};
+use pdf::primitive::{Dictionary, Primitive};
+
+use crate::{BBox, PositionedImage, TextBlock, bbox_union, points_bbox, resolve_primitive};
+
+/// Limit on the nesting of structure elements and page tree nodes.
+const MAX_STRUCTURE_DEPTH: usize = 64;
+
+/// A marked-content sequence opened by `BMC` or `BDC`.
+pub struct MarkedSpan {
+    pub mcid: Option<u32>,
+    pub actual_text: Option<String>,
+    /// Number of items produced before the sequence began.
+    pub start: usize,
+}
+
+/// Marked-content sequences open at the current point of a content stream.
+#[derive(Default)]
+pub struct MarkedContentStack {
+    spans: Vec<MarkedSpan>,
+}
+
+impl MarkedContentStack {
+    /// Open a sequence; `start` is the number of items produced so far and `in_form` whether
+    /// the sequence is in the content stream of a Form XObject.
+    pub fn begin(
+        &mut self,
+        properties: Option<&Primitive>,
+        resources: Option<&MaybeRef<Resources>>,
+        resolver: &impl Resolve,
+        start: usize,
+        in_form: bool,
+    ) {
+        let properties = properties.and_then(|value| property_list(value, resources, resolver));
+        let mcid = properties
+            .as_ref()
+            .filter(|_| !in_form)
+            .and_then(|dict| dict.get("MCID"))
+            .and_then(|value| value.as_integer().ok())
+            .and_then(|mcid| u32::try_from(mcid).ok());
+        let actual_text = properties
+            .as_ref()
+            .and_then(|dict| text_entry(dict, "ActualText", resolver));
+        self.spans.push(MarkedSpan {
+            mcid,
+            actual_text,
+            start,
+        });
+    }
+
+    pub fn end(&mut self) -> Option<MarkedSpan> {
+        self.spans.pop()
+    }
+
+    /// MCID of the innermost sequence that has one.
+    pub fn mcid(&self) -> Option<u32> {
+        self.spans.iter().rev().find_map(|span| span.mcid)
+    }
+}
+
+/// Property list of a `BDC` operator, given inline or as a name in `/Properties`.
+fn property_list(
+    value: &Primitive,
+    resources: Option<&MaybeRef<Resources>>,
+    resolver: &impl Resolve,
+) -> Option<Dictionary> {
+    match value {
+        Primitive::Dictionary(dict) => Some(dict.clone()),
+        Primitive::Name(name) => resources
+            .and_then(|res| {
+                res.properties
+                    .iter()
+                    .find(|(key, _)| key.as_str() == name.as_str())
+            })
+            .map(|(_, dict)| (**dict).clone()),
+        Primitive::Reference(_) => resolve_primitive(value, resolver)
+            .and_then(Primitive::into_dictionary)
+            .ok(),
+        _ => None,
+    }
+}
+
+/// Text string entry such as `/ActualText`, `/Alt` or `/T`.
+fn text_entry(dict: &Dictionary, key: &str, resolver: &impl Resolve) -> Option<String> {
+    let value = resolve_primitive(dict.get(key)?, resolver).ok()?;
+    Some(value.as_string().ok()?.to_string_lossy())
+}
+
+/// A structure element with its role mapped to a standard structure type.
+pub struct StructElement {
+    pub role: String,
+    /// Role as written in `/S`, before `/RoleMap`.
+    pub raw_role: String,
+    pub title: Option<String>,
+    pub lang: Option<String>,
+    pub alt: Option<String>,
+    pub actual_text: Option<String>,
+    pub page_index: Option<usize>,
+    pub kids: Vec<StructKid>,
+}
+
+pub enum StructKid {
+    Element(StructElement),
+    MarkedContent {
+        page_index: Option<usize>,
+        mcid: u32,
+    },
+    /// Reference to a whole object, such as an annotation or XObject.
+    Object {
+        page_index: Option<usize>,
+    },
+}
+
+impl StructElement {
+    /// Pages holding marked content of this element or its descendants.
+    pub fn pages(&self, pages: &mut HashSet<usize>) {
+        for kid in &self.kids {
+            match kid {
+                StructKid::Element(element) => element.pages(pages),
+                StructKid::MarkedContent {
+                    page_index: Some(page_index),
+                    ..
+                } => {
+                    pages.insert(*page_index);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Object references of the pages in document order, read from the raw page tree.
+pub fn page_references(
+    catalog: &Dictionary,
+    resolver: &impl Resolve,
+) -> Result<Vec<PlainRef>, PdfError> {
+    let mut pages = Vec::new();
+    if let Some(Primitive::Reference(root)) = catalog.get("Pages") {
+        collect_pages(*root, resolver, 0, &mut pages)?;
+    }
+    Ok(pages)
+}
+
+fn collect_pages(
+    node: PlainRef,
+    resolver: &impl Resolve,
+    depth: usize,
+    pages: &mut Vec<PlainRef>,
+) -> Result<(), PdfError> {
+    if depth > MAX_STRUCTURE_DEPTH {
+        return Ok(());
+    }
+    let dict = resolver.resolve(node)?.into_dictionary()?;
+    match dict.get("Kids") {
+        Some(kids) => {
+            for kid in resolve_primitive(kids, resolver)?.into_array()? {
+                if let Primitive::Reference(kid) = kid {
+                    collect_pages(kid, resolver, depth + 1, pages)?;
+                }
+            }
+        }
+        None => pages.push(node),
+    }
+    Ok(())
+}
+
+/// Read the structure tree of the document; untagged documents give an empty list.
+pub fn read_structure_tree(
+    catalog: &Dictionary,
+    pages: &[PlainRef],
+    resolver: &impl Resolve,
+) -> Result<Vec<StructElement>, PdfError> {
+    let Some(root) = catalog.get("StructTreeRoot") else {
+        return Ok(Vec::new());
+    };
+    let root = resolve_primitive(root, resolver)?.into_dictionary()?;
+    let mut role_map = HashMap::new();
+    if let Some(map) = root.get("RoleMap") {
+        for (name, role) in resolve_primitive(map, resolver)?.into_dictionary()?.iter() {
+            if let Ok(role) = role.as_name() {
+                role_map.insert(name.as_str().to_owned(), role.to_owned());
+            }
+        }
+    }
+    let mut reader = TreeReader {
+        resolver,
+        pages,
+        role_map,
+        visited: HashSet::new(),
+    };
+    let mut elements = Vec::new();
+    if let Some(kids) = root.get("K") {
+        for kid in reader.kids(kids, None, 0)? {
+            if let StructKid::Element(element) = kid {
+                elements.push(element);
+            }
+        }
+    }
+    Ok(elements)
+}
+
+struct TreeReader<'a, R: Resolve> {
+    resolver: &'a R,
+    pages: &'a [PlainRef],
+    role_map: HashMap<String, String>,
+    /// Elements already read, so shared or cyclic references are read once.
+    visited: HashSet<PlainRef>,
+}
+
+impl<R: Resolve> TreeReader<'_, R> {
+    fn page_index(&self, dict: &Dictionary) -> Option<usize> {
+        match dict.get("Pg") {
+            Some(Primitive::Reference(page)) => self.pages.iter().position(|p| p == page),
+            _ => None,
+        }
+    }
+
+    /// Follow `/RoleMap` until a role without a mapping, guarding against cycles.
+    fn standard_role(&self, role: &str) -> String {
+        let mut current = role;
+        for _ in 0..self.role_map.len() {
+            match self.role_map.get(current) {
+                Some(mapped) if mapped != current => current = mapped,
+                _ => break,
+            }
+        }
+        current.to_owned()
+    }
+
+    fn kids(
+        &mut self,
+        value: &Primitive,
+        page_index: Option<usize>,
+        depth: usize,
+    ) -> Result<Vec<StructKid>, PdfError> {
+        if depth > MAX_STRUCTURE_DEPTH {
+            return Ok(Vec::new());
+        }
+        let mut kids = Vec::new();
+        match value {
+            Primitive::Array(items) => {
+                for item in items {
+                    kids.extend(self.kids(item, page_index, depth)?);
+                }
+            }
+            Primitive::Integer(mcid) => {
+                if let Ok(mcid) = u32::try_from(*mcid) {
+                    kids.push(StructKid::MarkedContent { page_index, mcid });
+                }
+            }
+            Primitive::Reference(reference) => {
+                if self.visited.insert(*reference) {
+                    let resolved = self.resolver.resolve(*reference)?;
+                    kids.extend(self.kids(&resolved, page_index, depth)?);
+                }
+            }
+            Primitive::Dictionary(dict) => {
+                let page_index = self.page_index(dict).or(page_index);
+                let kind = dict.get("Type").and_then(|value| value.as_name().ok());
+                // An MCID in a form's stream (`/Stm`) does not number the page's content.
+                let mcid = dict
+                    .get("MCID")
+                    .filter(|_| dict.get("Stm").is_none())
+                    .and_then(|value| value.as_integer().ok());
+                if let Some(mcid) = mcid {
+                    if let Ok(mcid) = u32::try_from(mcid) {
+                        kids.push(StructKid::MarkedContent { page_index, mcid });
+                    }
+                } else if kind == Some("OBJR") {
+                    kids.push(StructKid::Object { page_index });
+                } else if dict.get("S").is_some() {
+                    kids.push(StructKid::Element(self.element(dict, page_index, depth)?));
+                }
+            }
+            _ => {}
+        }
+        Ok(kids)
+    }
+
+    fn element(
+        &mut self,
+        dict: &Dictionary,
+        page_index: Option<usize>,
+        depth: usize,
+    ) -> Result<StructElement, PdfError> {
+        let raw_role = dict
+            .get("S")
+            .and_then(|value| value.as_name().ok())
+            .unwrap_or_default()
+            .to_owned();
+        let kids = match dict.get("K") {
+            Some(kids) => self.kids(kids, page_index, depth + 1)?,
+            None => Vec::new(),
+        };
+        Ok(StructElement {
+            role: self.standard_role(&raw_role),
+            raw_role,
+            title: text_entry(dict, "T", self.resolver),
+            lang: text_entry(dict, "Lang", self.resolver),
+            alt: text_entry(dict, "Alt", self.resolver),
+            actual_text: text_entry(dict, "ActualText", self.resolver),
+            page_index,
+            kids,
+        })
+    }
+}
+
+/// Content of one marked-content sequence on a page.
+#[derive(Default)]
+pub struct MarkedContentItem {
+    pub text: String,
+    pub bbox: Option<BBox>,
+    /// Kinds of content in the sequence: `text`, `image` and `path`.
+    pub kinds: Vec<&'static str>,
+    /// Baseline of the last text block, used to separate lines.
+    last_baseline: Option<f32>,
+}
+
+impl MarkedContentItem {
+    fn add(&mut self, kind: &'static str, bbox: BBox) {
+        self.bbox = Some(self.bbox.map_or(bbox, |current| bbox_union(current, bbox)));
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+    }
+}
+
+/// Group the content produced by the walkers for one page by MCID.
+pub fn marked_content_items(
+    blocks: &[TextBlock],
+    images: &[PositionedImage],
+    paths: &[(String, Vec<(f32, f32)>)],
+    path_mcids: &[Option<u32>],
+) -> HashMap<u32, MarkedContentItem> {
+    let mut items: HashMap<u32, MarkedContentItem> = HashMap::new();
+    for block in blocks {
+        let Some(mcid) = block.mcid else {
+            continue;
+        };
+        let item = items.entry(mcid).or_default();
+        if item
+            .last_baseline
+            .is_some_and(|baseline| (baseline - block.baseline_y).abs() > block.font_size * 0.5)
+        {
+            item.text.push('\n');
+        }
+        item.text.push_str(&block.text);
+        item.last_baseline = Some(block.baseline_y);
+        item.add("text", (block.x0, block.y0, block.x1, block.y1));
+    }
+    for image in images {
+        if let Some(mcid) = image.mcid {
+            let bbox = (image.x0, image.y0, image.x1, image.y1);
+            items.entry(mcid).or_default().add("image", bbox);
+        }
+    }
+    for ((_, points), mcid) in paths.iter().zip(path_mcids) {
+        if let (Some(mcid), Some(bbox)) = (mcid, points_bbox(points)) {
+            items.entry(*mcid).or_default().add("path", bbox);
+        }
+    }
+    items
+}
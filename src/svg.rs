@@ -0,0 +1,405 @@
+//! Native SVG conversion of a page, without rasterizing it.
+//!
+//! Paths, images and text are emitted in painting order from the same walk as
+//! `extract_shapes`, in user space under one group that maps the page (CropBox and `/Rotate`)
+//! onto the SVG viewport, whose units are points. Clipping paths become nested `<clipPath>`
+//! elements, and the `/BBox` of each Form XObject clips what the form draws. Text becomes
+//! `<text>` elements placed glyph by glyph from `collect_text_blocks`, with the fonts named but
+//! not embedded. The clipping text render modes (`Tr` 4 to 7) are not reproduced: such text
+//! does not clip what is drawn after it.
+
+use pdf::error::PdfError;
+use thiserror::Error;
+
+use crate::ocr_xml::escape_xml;
+use crate::shapes::{PathShape, svg_number};
+use crate::{
+    DEFAULT_ASCENT, DEFAULT_DESCENT, FormStack, PageGeometry, PathSink, PathStyle, PdfPage,
+    PlacedImage, PlacedText, TextBlock, TextGlyph, collect_text_blocks, get_page, open_pdf,
+    project_glyph, walk_path_ops,
+};
+
+#[derive(Debug, Error)]
+pub enum SvgError {
+    #[error("PDF error: {0}")]
+    Pdf(#[from] PdfError),
+    #[error("page {0} has non-positive dimensions")]
+    PageGeometry(usize),
+}
+
+/// Convert one page of a PDF into a standalone SVG document.
+pub fn page_to_svg(path: &str, page_index: usize) -> Result<String, SvgError> {
+    let pdf = open_pdf(path)?;
+    let page = get_page(&pdf, page_index)?;
+    let page_ref: &PdfPage = &page;
+    let geometry = PageGeometry::from_pdf_page(page_ref, 1.0)
+        .map_err(|_| SvgError::PageGeometry(page_index))?;
+    let (width, height) = geometry.pixel_size();
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         version=\"1.1\" width=\"{w}pt\" height=\"{h}pt\" viewBox=\"0 0 {w} {h}\">\n",
+        w = svg_number(width as f32),
+        h = svg_number(height as f32)
+    ));
+    let resolver = pdf.resolver();
+    let resources = page_ref.resources().ok();
+    if let Some(content) = &page_ref.contents {
+        let operations = content.operations(&resolver)?;
+        let mut sink = PathSink {
+            with_images: true,
+            ..PathSink::default()
+        };
+        walk_path_ops(
+            &operations,
+            resources,
+            &resolver,
+            PathStyle::default(),
+            &mut FormStack::default(),
+            &mut sink,
+        )?;
+        let blocks = collect_text_blocks(&operations, resources, &resolver)?;
+
+        write_clip_paths(&mut out, &sink.clips);
+        out.push_str(&format!(
+            "<g transform=\"{}\">\n",
+            page_transform(&geometry)
+        ));
+        let placement = |block: &TextBlock| sink.texts.get(block.show_index).unwrap_or(&UNPLACED);
+        let mut images = sink.images.iter().enumerate().peekable();
+        let mut texts = blocks.iter().peekable();
+        for index in 0..=sink.shapes.len() {
+            // Images and text drawn before this shape, in the order they were drawn.
+            loop {
+                let image = images.peek().filter(|(_, image)| image.order <= index);
+                let text = texts
+                    .peek()
+                    .map(|block| placement(block))
+                    .filter(|text| text.order <= index);
+                let text_first = match (image, text) {
+                    (Some((image_index, _)), Some(text)) => text.images <= *image_index,
+                    (None, Some(_)) => true,
+                    (Some(_), None) => false,
+                    (None, None) => break,
+                };
+                if text_first {
+                    if let Some(block) = texts.next() {
+                        write_text(&mut out, block, placement(block).clip_path);
+                    }
+                } else if let Some((_, image)) = images.next() {
+                    write_image(&mut out, image);
+                }
+            }
+            if let Some(shape) = sink.shapes.get(index) {
+                write_shape(&mut out, shape);
+            }
+        }
+        out.push_str("</g>\n");
+    }
+    out.push_str("</svg>\n");
+    Ok(out)
+}
+
+/// Placement of a block whose operator the path walk did not record, drawn unclipped first.
+const UNPLACED: PlacedText = PlacedText {
+    clip_path: None,
+    order: 0,
+    images: 0,
+};
+
+/// `matrix(...)` mapping user space onto the displayed page, probed from the page geometry.
+fn page_transform(geometry: &PageGeometry) -> String {
+    let (e, f) = geometry.to_pixels(0.0, 0.0);
+    let (ax, ay) = geometry.to_pixels(1.0, 0.0);
+    let (cx, cy) = geometry.to_pixels(0.0, 1.0);
+    svg_matrix([ax - e, ay - f, cx - e, cy - f, e, f].map(|value| value as f32))
+}
+
+fn svg_matrix(values: [f32; 6]) -> String {
+    let values: Vec<String> = values.iter().map(|&value| svg_number(value)).collect();
+    format!("matrix({})", values.join(" "))
+}
+
+fn svg_color((r, g, b): (f32, f32, f32)) -> String {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b))
+}
+
+/// Paint of a path: its color, or gray for patterns and shadings, which are not reproduced.
+fn svg_paint(color: Option<(f32, f32, f32)>) -> String {
+    svg_color(color.unwrap_or((0.5, 0.5, 0.5)))
+}
+
+fn clip_id(index: usize) -> String {
+    format!("clip{}", index)
+}
+
+fn write_clip_paths(out: &mut String, clips: &[PathShape]) {
+    if clips.is_empty() {
+        return;
+    }
+    out.push_str("<defs>\n");
+    for (index, clip) in clips.iter().enumerate() {
+        // Clip paths are defined in the same user space as the elements that refer to them.
+        out.push_str(&format!(
+            "<clipPath id=\"{}\" clipPathUnits=\"userSpaceOnUse\"{}>",
+            clip_id(index),
+            clip_path_attribute(clip.style.clip_path)
+        ));
+        out.push_str(&format!(
+            "<path d=\"{}\" clip-rule=\"{}\"/></clipPath>\n",
+            clip.svg_path_data(),
+            clip.style.fill_rule.unwrap_or("nonzero")
+        ));
+    }
+    out.push_str("</defs>\n");
+}
+
+fn clip_path_attribute(clip_path: Option<usize>) -> String {
+    match clip_path {
+        Some(index) => format!(" clip-path=\"url(#{})\"", clip_id(index)),
+        None => String::new(),
+    }
+}
+
+fn write_shape(out: &mut String, shape: &PathShape) {
+    let style = &shape.style;
+    let mut attributes = vec![format!("d=\"{}\"", shape.svg_path_data())];
+    if style.paint.fills() {
+        attributes.push(format!("fill=\"{}\"", svg_paint(style.fill_color)));
+        if let Some(rule) = style.fill_rule {
+            attributes.push(format!("fill-rule=\"{}\"", rule));
+        }
+        if style.fill_alpha < 1.0 {
+            attributes.push(format!("fill-opacity=\"{}\"", svg_number(style.fill_alpha)));
+        }
+    } else {
+        attributes.push("fill=\"none\"".to_string());
+    }
+    if style.paint.strokes() {
+        attributes.push(format!("stroke=\"{}\"", svg_paint(style.stroke_color)));
+        let width = style.effective_line_width();
+        if width > 0.0 {
+            attributes.push(format!("stroke-width=\"{}\"", svg_number(width)));
+        } else {
+            // A zero width is the thinnest line the device can show.
+            attributes.push("stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"".to_string());
+        }
+        if style.line_cap != "butt" {
+            attributes.push(format!("stroke-linecap=\"{}\"", style.line_cap));
+        }
+        if style.line_join != "miter" {
+            attributes.push(format!("stroke-linejoin=\"{}\"", style.line_join));
+        }
+        if !style.dash_array.is_empty() {
+            let scale = style.ctm_scale();
+            let dashes: Vec<String> = style
+                .dash_array
+                .iter()
+                .map(|&length| svg_number(length * scale))
+                .collect();
+            attributes.push(format!("stroke-dasharray=\"{}\"", dashes.join(" ")));
+            if style.dash_phase != 0.0 {
+                attributes.push(format!(
+                    "stroke-dashoffset=\"{}\"",
+                    svg_number(style.dash_phase * scale)
+                ));
+            }
+        }
+        if style.stroke_alpha < 1.0 {
+            attributes.push(format!(
+                "stroke-opacity=\"{}\"",
+                svg_number(style.stroke_alpha)
+            ));
+        }
+    }
+    let element = format!("<path {}/>", attributes.join(" "));
+    write_clipped(out, style.clip_path, &element);
+}
+
+fn write_clipped(out: &mut String, clip_path: Option<usize>, element: &str) {
+    match clip_path {
+        Some(_) => out.push_str(&format!(
+            "<g{}>{}</g>\n",
+            clip_path_attribute(clip_path),
+            element
+        )),
+        None => {
+            out.push_str(element);
+            out.push('\n');
+        }
+    }
+}
+
+fn write_image(out: &mut String, placed: &PlacedImage) {
+    let image = &placed.image;
+    let mime = match image.format.as_str() {
+        "png" => "image/png",
+        "jpeg" => "image/jpeg",
+        other => {
+            // Browsers cannot show the other encodings, so they are left out.
+            out.push_str(&format!(
+                "<!-- {}x{} {} image not embedded -->\n",
+                image.width,
+                image.height,
+                escape_xml(other)
+            ));
+            return;
+        }
+    };
+    let ctm = placed.ctm;
+    // The image fills the unit square of its CTM, with its first row at the top (y = 1).
+    let element = format!(
+        "<image x=\"0\" y=\"0\" width=\"1\" height=\"1\" preserveAspectRatio=\"none\" \
+         transform=\"{} matrix(1 0 0 -1 0 1)\" xlink:href=\"data:{};base64,{}\"/>",
+        svg_matrix([ctm.a, ctm.b, ctm.c, ctm.d, ctm.e, ctm.f]),
+        mime,
+        base64_encode(&image.data)
+    );
+    write_clipped(out, placed.clip_path, &element);
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for position in 0..4 {
+            if position <= chunk.len() {
+                let index = (group >> (18 - 6 * position)) & 0x3f;
+                encoded.push(ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Whether a glyph advances down the page, as in vertical writing; its glyphs stay upright.
+fn is_downward(glyph: &TextGlyph) -> bool {
+    glyph.direction.1 < -0.9
+}
+
+fn write_text(out: &mut String, block: &TextBlock, clip_path: Option<usize>) {
+    let Some(first) = block.glyphs.first() else {
+        return;
+    };
+    let mut text = String::new();
+    let mut xs: Vec<String> = Vec::new();
+    let mut ys: Vec<String> = Vec::new();
+    let transform = if is_downward(first) {
+        // Upright glyphs stacked down the page, each on the baseline of its em box.
+        let descent = -DEFAULT_DESCENT / (DEFAULT_ASCENT - DEFAULT_DESCENT);
+        for glyph in &block.glyphs {
+            let chars: Vec<char> = glyph.text.chars().collect();
+            let height = glyph.y1 - glyph.y0;
+            for (k, &c) in chars.iter().enumerate() {
+                let top = glyph.y1 - height * k as f32 / chars.len() as f32;
+                let bottom = top - height / chars.len() as f32;
+                text.push(c);
+                xs.push(svg_number(glyph.x0));
+                ys.push(svg_number(-(bottom + (top - bottom) * descent)));
+            }
+        }
+        svg_matrix([1.0, 0.0, 0.0, -1.0, 0.0, 0.0])
+    } else {
+        // Text space of the first glyph: x along the baseline, y down across it.
+        let (dx, dy) = first.direction;
+        let (ox, oy) = (first.origin_x, first.origin_y);
+        for glyph in &block.glyphs {
+            let chars: Vec<char> = glyph.text.chars().collect();
+            let start = (glyph.origin_x - ox) * dx + (glyph.origin_y - oy) * dy;
+            let (low, high) = project_glyph(glyph, (dx, dy));
+            for (k, &c) in chars.iter().enumerate() {
+                text.push(c);
+                xs.push(svg_number(
+                    start + (high - low) * k as f32 / chars.len() as f32,
+                ));
+            }
+        }
+        ys.push("0".to_string());
+        svg_matrix([dx, dy, dy, -dx, ox, oy])
+    };
+    if text.is_empty() {
+        return;
+    }
+
+    let flags = &block.font_flags;
+    let generic = if flags.fixed_pitch {
+        "monospace"
+    } else if flags.serif {
+        "serif"
+    } else {
+        "sans-serif"
+    };
+    let family = match &block.font_name {
+        Some(name) => format!("'{}', {}", name.replace('\'', ""), generic),
+        None => generic.to_string(),
+    };
+    let mut attributes = vec![
+        format!("transform=\"{}\"", transform),
+        format!("x=\"{}\"", xs.join(" ")),
+        format!("y=\"{}\"", ys.join(" ")),
+        format!("font-family=\"{}\"", escape_xml(&family)),
+        format!("font-size=\"{}\"", svg_number(block.font_size)),
+        // Patterns are not reproduced; such text is shown in black.
+        format!(
+            "fill=\"{}\"",
+            svg_color(block.fill_color.unwrap_or((0.0, 0.0, 0.0)))
+        ),
+    ];
+    if flags.bold {
+        attributes.push("font-weight=\"bold\"".to_string());
+    }
+    if flags.italic {
+        attributes.push("font-style=\"italic\"".to_string());
+    }
+    if block.is_invisible() {
+        // Kept so the text of OCR layers can still be selected and searched.
+        attributes.push("fill-opacity=\"0\"".to_string());
+    }
+    let element = format!(
+        "<text xml:space=\"preserve\" {}>{}</text>",
+        attributes.join(" "),
+        escape_xml(&text)
+    );
+    write_clipped(out, clip_path, &element);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_short_tails() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"M"), "TQ==");
+        assert_eq!(base64_encode(b"Ma"), "TWE=");
+        assert_eq!(base64_encode(b"Man"), "TWFu");
+        assert_eq!(base64_encode(b"Many"), "TWFueQ==");
+        assert_eq!(base64_encode(&[0xff, 0xfe, 0xfd, 0xfc, 0xfb]), "//79/Ps=");
+    }
+
+    #[test]
+    fn colors_are_clamped_hex() {
+        assert_eq!(svg_color((1.0, 0.5, 0.0)), "#ff8000");
+        assert_eq!(svg_color((1.5, -0.2, 0.2)), "#ff0033");
+        assert_eq!(svg_paint(None), "#808080");
+    }
+
+    #[test]
+    fn matrix_uses_svg_numbers() {
+        assert_eq!(
+            svg_matrix([1.0, 0.0, -0.0, 1.0, 10.25, 0.0004]),
+            "matrix(1 0 0 1 10.25 0)"
+        );
+    }
+}
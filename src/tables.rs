@@ -0,0 +1,583 @@
+//! Table detection from ruling lines, with a whitespace-alignment fallback.
+//!
+//! Ruled tables are found by connecting horizontal and vertical rules (stroked lines and
+//! rectangles, and thin filled rectangles, from `collect_paths`) into grids. A grid line that is
+//! missing between two neighbouring cells merges them into one spanning cell, and a grid that
+//! ends up as a single cell is a frame or a box, not a table. Text that is not inside a ruled
+//! table is scanned for runs of lines whose words line up in columns separated by whitespace.
+
+use std::collections::HashMap;
+
+use crate::{BBox, CaptionInfo, PaintMode, TextWord, bbox_union, cmp_f32};
+
+/// Maximum thickness, or deviation from the axis, of a segment treated as a rule.
+const RULE_TOLERANCE: f32 = 1.5;
+/// Distance within which rules are considered to touch.
+const JOIN_TOLERANCE: f32 = 2.0;
+/// Minimum length of a rule.
+const MIN_RULE_LENGTH: f32 = 4.0;
+/// Share of a cell edge that must be drawn for the edge to separate two cells.
+const EDGE_COVERAGE: f32 = 0.5;
+/// Gap between words, relative to the font size, that separates cells of a borderless table.
+const CELL_GAP_RATIO: f32 = 1.0;
+/// Minimum number of aligned lines that make a borderless table.
+const MIN_WHITESPACE_ROWS: usize = 3;
+/// Median cell length above which aligned lines are treated as column text, not a table.
+const MAX_MEDIAN_CELL_CHARS: usize = 30;
+
+/// How a table was detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableMethod {
+    Lines,
+    Whitespace,
+}
+
+impl TableMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TableMethod::Lines => "lines",
+            TableMethod::Whitespace => "whitespace",
+        }
+    }
+}
+
+/// A cell anchored at its top-left grid position.
+#[derive(Debug, Clone)]
+pub struct TableCell {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub bbox: BBox,
+    pub text: String,
+}
+
+#[derive(Clone)]
+pub struct Table {
+    pub bbox: BBox,
+    pub method: TableMethod,
+    pub row_count: usize,
+    pub column_count: usize,
+    pub cells: Vec<TableCell>,
+    pub captions: Vec<CaptionInfo>,
+}
+
+impl Table {
+    /// Cell texts on the row/column grid; positions covered by a spanning cell are `None`.
+    pub fn grid(&self) -> Vec<Vec<Option<&str>>> {
+        let mut grid = vec![vec![None; self.column_count]; self.row_count];
+        for cell in &self.cells {
+            if let Some(slot) = grid
+                .get_mut(cell.row)
+                .and_then(|row| row.get_mut(cell.column))
+            {
+                *slot = Some(cell.text.as_str());
+            }
+        }
+        grid
+    }
+}
+
+/// A path segment: its kind, its points in user space and how its path was painted.
+pub type PaintedSegment = (String, Vec<(f32, f32)>, PaintMode);
+
+/// An axis-aligned rule: `position` is y for horizontal rules and x for vertical ones.
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    position: f32,
+    start: f32,
+    end: f32,
+}
+
+/// Detect tables from painted path segments and words on one page.
+pub fn detect_tables(segments: &[PaintedSegment], words: &[TextWord]) -> Vec<Table> {
+    let (horizontal, vertical) = collect_rules(segments);
+    let mut tables: Vec<Table> = rule_grids(&horizontal, &vertical)
+        .into_iter()
+        .filter_map(|(horizontal, vertical)| ruled_table(&horizontal, &vertical, words))
+        .collect();
+    let free_words: Vec<&TextWord> = words
+        .iter()
+        .filter(|word| {
+            let center = word_center(word);
+            !tables.iter().any(|table| contains(table.bbox, center))
+        })
+        .collect();
+    tables.extend(whitespace_tables(&free_words));
+    tables.sort_by(|a, b| cmp_f32(b.bbox.3, a.bbox.3));
+    tables
+}
+
+fn word_center(word: &TextWord) -> (f32, f32) {
+    let (x0, y0, x1, y1) = word.bbox;
+    ((x0 + x1) / 2.0, (y0 + y1) / 2.0)
+}
+
+fn contains(bbox: BBox, point: (f32, f32)) -> bool {
+    point.0 >= bbox.0 && point.0 <= bbox.2 && point.1 >= bbox.1 && point.1 <= bbox.3
+}
+
+/// Split line and rectangle segments into merged horizontal and vertical rules. Filled
+/// rectangles only count when they are thin, so background boxes do not become grids.
+fn collect_rules(segments: &[PaintedSegment]) -> (Vec<Rule>, Vec<Rule>) {
+    let mut horizontal = Vec::new();
+    let mut vertical = Vec::new();
+    let mut add_edge = |(ax, ay): (f32, f32), (bx, by): (f32, f32)| {
+        if (ay - by).abs() <= RULE_TOLERANCE && (ax - bx).abs() >= MIN_RULE_LENGTH {
+            horizontal.push(Rule {
+                position: (ay + by) / 2.0,
+                start: ax.min(bx),
+                end: ax.max(bx),
+            });
+        } else if (ax - bx).abs() <= RULE_TOLERANCE && (ay - by).abs() >= MIN_RULE_LENGTH {
+            vertical.push(Rule {
+                position: (ax + bx) / 2.0,
+                start: ay.min(by),
+                end: ay.max(by),
+            });
+        }
+    };
+    for (kind, points, paint) in segments {
+        match (kind.as_str(), points.as_slice()) {
+            ("line", [a, b]) if paint.strokes() => add_edge(*a, *b),
+            ("rect", points) if !points.is_empty() && paint.is_painted() => {
+                let (x0, y0, x1, y1) = points.iter().fold(
+                    (
+                        f32::INFINITY,
+                        f32::INFINITY,
+                        f32::NEG_INFINITY,
+                        f32::NEG_INFINITY,
+                    ),
+                    |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                );
+                let (width, height) = (x1 - x0, y1 - y0);
+                if height <= RULE_TOLERANCE || width <= RULE_TOLERANCE {
+                    let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+                    if height <= RULE_TOLERANCE {
+                        add_edge((x0, cy), (x1, cy));
+                    } else {
+                        add_edge((cx, y0), (cx, y1));
+                    }
+                } else if paint.strokes() {
+                    add_edge((x0, y0), (x1, y0));
+                    add_edge((x0, y1), (x1, y1));
+                    add_edge((x0, y0), (x0, y1));
+                    add_edge((x1, y0), (x1, y1));
+                }
+            }
+            _ => {}
+        }
+    }
+    (merge_rules(horizontal), merge_rules(vertical))
+}
+
+/// Join collinear rules that overlap or nearly touch.
+fn merge_rules(mut rules: Vec<Rule>) -> Vec<Rule> {
+    rules.sort_by(|a, b| cmp_f32(a.position, b.position).then_with(|| cmp_f32(a.start, b.start)));
+    let mut merged: Vec<Rule> = Vec::new();
+    for rule in rules {
+        match merged.iter_mut().rev().find(|existing| {
+            (existing.position - rule.position).abs() <= RULE_TOLERANCE
+                && rule.start <= existing.end + JOIN_TOLERANCE
+        }) {
+            Some(existing) => existing.end = existing.end.max(rule.end),
+            None => merged.push(rule),
+        }
+    }
+    merged
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find_root(parents, a), find_root(parents, b));
+    if a != b {
+        parents[b] = a;
+    }
+}
+
+/// Group rules into connected grids with at least two rules in each direction.
+fn rule_grids(horizontal: &[Rule], vertical: &[Rule]) -> Vec<(Vec<Rule>, Vec<Rule>)> {
+    let count = horizontal.len() + vertical.len();
+    let mut parents: Vec<usize> = (0..count).collect();
+    for (h_index, h) in horizontal.iter().enumerate() {
+        for (v_index, v) in vertical.iter().enumerate() {
+            let crosses = v.position >= h.start - JOIN_TOLERANCE
+                && v.position <= h.end + JOIN_TOLERANCE
+                && h.position >= v.start - JOIN_TOLERANCE
+                && h.position <= v.end + JOIN_TOLERANCE;
+            if crosses {
+                union(&mut parents, h_index, horizontal.len() + v_index);
+            }
+        }
+    }
+    let mut components: HashMap<usize, (Vec<Rule>, Vec<Rule>)> = HashMap::new();
+    for index in 0..count {
+        let root = find_root(&mut parents, index);
+        let entry = components.entry(root).or_default();
+        match index.checked_sub(horizontal.len()) {
+            Some(v_index) => entry.1.push(vertical[v_index]),
+            None => entry.0.push(horizontal[index]),
+        }
+    }
+    components
+        .into_values()
+        .filter(|(h, v)| h.len() >= 2 && v.len() >= 2)
+        .collect()
+}
+
+/// Distinct positions of rules, within [`JOIN_TOLERANCE`], in ascending order.
+fn grid_positions(rules: &[Rule]) -> Vec<f32> {
+    let mut positions: Vec<f32> = rules.iter().map(|rule| rule.position).collect();
+    positions.sort_by(|a, b| cmp_f32(*a, *b));
+    positions.dedup_by(|a, b| (*a - *b).abs() <= JOIN_TOLERANCE);
+    positions
+}
+
+/// Whether rules at `position` cover enough of the edge `start..end`.
+fn edge_drawn(rules: &[Rule], position: f32, start: f32, end: f32) -> bool {
+    let covered: f32 = rules
+        .iter()
+        .filter(|rule| (rule.position - position).abs() <= JOIN_TOLERANCE)
+        .map(|rule| (rule.end.min(end) - rule.start.max(start)).max(0.0))
+        .sum();
+    covered >= (end - start) * EDGE_COVERAGE
+}
+
+fn ruled_table(horizontal: &[Rule], vertical: &[Rule], words: &[TextWord]) -> Option<Table> {
+    let xs = grid_positions(vertical);
+    let mut ys = grid_positions(horizontal);
+    ys.reverse();
+    if xs.len() < 2 || ys.len() < 2 {
+        return None;
+    }
+    let (rows, columns) = (ys.len() - 1, xs.len() - 1);
+    let mut parents: Vec<usize> = (0..rows * columns).collect();
+    for row in 0..rows {
+        for column in 0..columns {
+            let index = row * columns + column;
+            if column + 1 < columns && !edge_drawn(vertical, xs[column + 1], ys[row + 1], ys[row]) {
+                union(&mut parents, index, index + 1);
+            }
+            if row + 1 < rows && !edge_drawn(horizontal, ys[row + 1], xs[column], xs[column + 1]) {
+                union(&mut parents, index, index + columns);
+            }
+        }
+    }
+    let mut spans: HashMap<usize, (usize, usize, usize, usize)> = HashMap::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            let root = find_root(&mut parents, row * columns + column);
+            let span = spans.entry(root).or_insert((row, column, row, column));
+            span.0 = span.0.min(row);
+            span.1 = span.1.min(column);
+            span.2 = span.2.max(row);
+            span.3 = span.3.max(column);
+        }
+    }
+    let mut cells: Vec<TableCell> = spans
+        .into_values()
+        .map(|(row, column, last_row, last_column)| TableCell {
+            row,
+            column,
+            row_span: last_row - row + 1,
+            column_span: last_column - column + 1,
+            bbox: (xs[column], ys[last_row + 1], xs[last_column + 1], ys[row]),
+            text: String::new(),
+        })
+        .collect();
+    if cells.len() < 2 {
+        return None;
+    }
+    cells.sort_by_key(|cell| (cell.row, cell.column));
+    for cell in &mut cells {
+        let inside: Vec<&TextWord> = words
+            .iter()
+            .filter(|word| contains(cell.bbox, word_center(word)))
+            .collect();
+        cell.text = join_words(inside);
+    }
+    Some(Table {
+        bbox: (xs[0], ys[rows], xs[columns], ys[0]),
+        method: TableMethod::Lines,
+        row_count: rows,
+        column_count: columns,
+        cells,
+        captions: Vec::new(),
+    })
+}
+
+/// Join words into text, lines top to bottom and words left to right.
+fn join_words(words: Vec<&TextWord>) -> String {
+    group_lines(words)
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Group words that share a baseline, top to bottom, each line sorted left to right.
+fn group_lines(mut words: Vec<&TextWord>) -> Vec<Vec<&TextWord>> {
+    words.sort_by(|a, b| cmp_f32(b.origin_y, a.origin_y));
+    let mut lines: Vec<Vec<&TextWord>> = Vec::new();
+    for word in words {
+        match lines.last_mut() {
+            Some(line)
+                if (line[0].origin_y - word.origin_y).abs()
+                    <= line[0].font_size.max(word.font_size) * 0.5 =>
+            {
+                line.push(word)
+            }
+            _ => lines.push(vec![word]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| cmp_f32(a.bbox.0, b.bbox.0));
+    }
+    lines
+}
+
+/// Number of whitespace-separated word clusters in a line.
+fn cluster_count(line: &[&TextWord]) -> usize {
+    1 + line
+        .windows(2)
+        .filter(|pair| {
+            let size = pair[0].font_size.max(pair[1].font_size);
+            pair[1].bbox.0 - pair[0].bbox.2 > size * CELL_GAP_RATIO
+        })
+        .count()
+}
+
+fn line_bbox(line: &[&TextWord]) -> BBox {
+    line.iter()
+        .map(|word| word.bbox)
+        .reduce(bbox_union)
+        .unwrap_or_default()
+}
+
+/// Find borderless tables: runs of adjacent lines with several cells whose words align in
+/// columns separated by whitespace gutters.
+fn whitespace_tables(words: &[&TextWord]) -> Vec<Table> {
+    let lines = group_lines(words.to_vec());
+    let mut runs: Vec<Vec<&Vec<&TextWord>>> = Vec::new();
+    let mut previous: Option<BBox> = None;
+    for line in &lines {
+        let bbox = line_bbox(line);
+        if cluster_count(line) < 2 {
+            previous = None;
+            continue;
+        }
+        let height = (bbox.3 - bbox.1).max(1.0);
+        match (runs.last_mut(), previous) {
+            (Some(run), Some(above)) if above.1 - bbox.3 <= height * 1.5 => run.push(line),
+            _ => runs.push(vec![line]),
+        }
+        previous = Some(bbox);
+    }
+    runs.into_iter()
+        .filter(|run| run.len() >= MIN_WHITESPACE_ROWS)
+        .filter_map(|run| whitespace_table(&run))
+        .collect()
+}
+
+fn whitespace_table(run: &[&Vec<&TextWord>]) -> Option<Table> {
+    let mut spans: Vec<(f32, f32)> = run
+        .iter()
+        .flat_map(|line| line.iter().map(|word| (word.bbox.0, word.bbox.2)))
+        .collect();
+    spans.sort_by(|a, b| cmp_f32(a.0, b.0));
+    let mut sizes: Vec<f32> = run
+        .iter()
+        .flat_map(|line| line.iter().map(|word| word.font_size))
+        .collect();
+    sizes.sort_by(|a, b| cmp_f32(*a, *b));
+    let gutter = sizes[sizes.len() / 2] * CELL_GAP_RATIO;
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+    for (start, end) in spans {
+        match columns.last_mut() {
+            Some(column) if start - column.1 <= gutter => column.1 = column.1.max(end),
+            _ => columns.push((start, end)),
+        }
+    }
+    if columns.len() < 2 {
+        return None;
+    }
+    let mut cells = Vec::new();
+    for (row, line) in run.iter().enumerate() {
+        let (_, y0, _, y1) = line_bbox(line);
+        for (column, &(x0, x1)) in columns.iter().enumerate() {
+            let inside: Vec<&TextWord> = line
+                .iter()
+                .copied()
+                .filter(|word| {
+                    let (cx, _) = word_center(word);
+                    cx >= x0 && cx <= x1
+                })
+                .collect();
+            cells.push(TableCell {
+                row,
+                column,
+                row_span: 1,
+                column_span: 1,
+                bbox: (x0, y0, x1, y1),
+                text: join_words(inside),
+            });
+        }
+    }
+    let mut lengths: Vec<usize> = cells
+        .iter()
+        .map(|cell| cell.text.chars().count())
+        .filter(|&length| length > 0)
+        .collect();
+    lengths.sort_unstable();
+    if lengths.is_empty() || lengths[lengths.len() / 2] > MAX_MEDIAN_CELL_CHARS {
+        return None;
+    }
+    let bbox = cells.iter().map(|cell| cell.bbox).reduce(bbox_union)?;
+    Some(Table {
+        bbox,
+        method: TableMethod::Whitespace,
+        row_count: run.len(),
+        column_count: columns.len(),
+        cells,
+        captions: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32, paint: PaintMode) -> PaintedSegment {
+        let points = vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1), (x0, y0)];
+        ("rect".to_string(), points, paint)
+    }
+
+    fn line(a: (f32, f32), b: (f32, f32)) -> PaintedSegment {
+        ("line".to_string(), vec![a, b], PaintMode::Stroke)
+    }
+
+    fn word(text: &str, x: f32, y: f32) -> TextWord {
+        TextWord {
+            text: text.to_string(),
+            bbox: (x, y, x + 20.0, y + 10.0),
+            origin_x: x,
+            origin_y: y + 2.0,
+            font_name: None,
+            font_size: 10.0,
+            block_index: 0,
+        }
+    }
+
+    /// A 100 x 100 box split into four cells by rules through its center.
+    fn grid_segments() -> Vec<PaintedSegment> {
+        vec![
+            rect(0.0, 0.0, 100.0, 100.0, PaintMode::Stroke),
+            line((50.0, 0.0), (50.0, 100.0)),
+            line((0.0, 50.0), (100.0, 50.0)),
+        ]
+    }
+
+    fn grid_words() -> Vec<TextWord> {
+        vec![
+            word("a", 10.0, 70.0),
+            word("b", 60.0, 70.0),
+            word("c", 10.0, 20.0),
+            word("d", 60.0, 20.0),
+        ]
+    }
+
+    #[test]
+    fn single_box_is_not_a_table() {
+        let segments = vec![rect(0.0, 0.0, 100.0, 100.0, PaintMode::Stroke)];
+        let words = vec![word("note", 10.0, 50.0)];
+        assert!(detect_tables(&segments, &words).is_empty());
+    }
+
+    #[test]
+    fn filled_background_gives_no_rules() {
+        let segments = vec![rect(0.0, 0.0, 100.0, 100.0, PaintMode::Fill)];
+        let (horizontal, vertical) = collect_rules(&segments);
+        assert!(horizontal.is_empty() && vertical.is_empty());
+    }
+
+    #[test]
+    fn two_by_two_grid() {
+        let tables = detect_tables(&grid_segments(), &grid_words());
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert_eq!(table.method, TableMethod::Lines);
+        assert_eq!((table.row_count, table.column_count), (2, 2));
+        assert_eq!(
+            table.grid(),
+            vec![vec![Some("a"), Some("b")], vec![Some("c"), Some("d")]]
+        );
+    }
+
+    #[test]
+    fn thin_filled_rectangles_are_rules() {
+        let segments = vec![
+            rect(0.0, 0.0, 100.0, 0.5, PaintMode::Fill),
+            rect(0.0, 49.75, 100.0, 50.25, PaintMode::Fill),
+            rect(0.0, 99.5, 100.0, 100.0, PaintMode::Fill),
+            rect(0.0, 0.0, 0.5, 100.0, PaintMode::Fill),
+            rect(49.75, 0.0, 50.25, 100.0, PaintMode::Fill),
+            rect(99.5, 0.0, 100.0, 100.0, PaintMode::Fill),
+        ];
+        let tables = detect_tables(&segments, &grid_words());
+        assert_eq!(tables.len(), 1);
+        assert_eq!((tables[0].row_count, tables[0].column_count), (2, 2));
+    }
+
+    #[test]
+    fn missing_rule_merges_cells() {
+        // The vertical rule only divides the bottom row, so the top row is one cell.
+        let segments = vec![
+            rect(0.0, 0.0, 100.0, 100.0, PaintMode::Stroke),
+            line((50.0, 0.0), (50.0, 50.0)),
+            line((0.0, 50.0), (100.0, 50.0)),
+        ];
+        let tables = detect_tables(&segments, &grid_words());
+        assert_eq!(tables.len(), 1);
+        let cells: Vec<(usize, usize, usize, usize, &str)> = tables[0]
+            .cells
+            .iter()
+            .map(|cell| {
+                let (row, column) = (cell.row, cell.column);
+                (
+                    row,
+                    column,
+                    cell.row_span,
+                    cell.column_span,
+                    cell.text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            cells,
+            vec![(0, 0, 1, 2, "a b"), (1, 0, 1, 1, "c"), (1, 1, 1, 1, "d")]
+        );
+    }
+
+    #[test]
+    fn fully_merged_grid_is_not_a_table() {
+        // Both inner rules are too short to separate any cells.
+        let segments = vec![
+            rect(0.0, 0.0, 100.0, 100.0, PaintMode::Stroke),
+            line((50.0, 0.0), (50.0, 10.0)),
+            line((0.0, 50.0), (10.0, 50.0)),
+        ];
+        assert!(detect_tables(&segments, &[]).is_empty());
+    }
+}